@@ -5,7 +5,7 @@ use ggez::graphics::{self, Point2, Mesh, DrawParam, Color};
 use ggez::{Context, GameResult, ContextBuilder, mouse};
 
 extern crate hex_test;
-use hex_test::{grid, HexShape, Orientation};
+use hex_test::{grid, HexShape, Orientation, Layout};
 use grid::initializers::HexagonalCenteredGridIterator;
 
 const DEFAULT_HEX_RADIUS: f32 = 25.0;
@@ -27,8 +27,7 @@ type MyHexGrid = grid::HexGrid<MyCoordSystem, SomeData, MyGridWrapper>;
 
 pub struct GraphicsData {
     origin_mesh: Mesh,
-    zoom: f32,
-    offset: (f32, f32)
+    layout: Layout
 }
 
 fn tuple_to_point((x, y): (f32, f32)) -> Point2 {
@@ -39,16 +38,6 @@ fn point_to_tuple(p: Point2) -> (f32, f32) {
     (p.coords.x, p.coords.y)
 }
 
-fn ui_scaling((x, y): (f32, f32), gfx_data: &GraphicsData) -> (f32, f32){
-    let ((off_x, off_y), scale) = (gfx_data.offset, gfx_data.zoom);
-    ((x * scale) + off_x, (y * scale) + off_y)
-}
-
-fn reverse_ui_scaling((x, y): (f32, f32), gfx_data: &GraphicsData) -> (f32, f32) {
-    let ((off_x, off_y), scale) = (gfx_data.offset, gfx_data.zoom);
-    ((x - off_x) / scale, (y - off_y) / scale)
-}
-
 fn point_data_to_mesh(ctx: &mut Context, data: [(f32, f32); 6]) -> Mesh {
     let vec: Vec<Point2> =  data.to_vec().into_iter().map(|(x, y)| Point2::new(x, y)).collect();
 
@@ -57,16 +46,16 @@ fn point_data_to_mesh(ctx: &mut Context, data: [(f32, f32); 6]) -> Mesh {
 
 fn draw_everything(ctx: &mut Context, hex_map: &MyHexGrid, gfx_data: &GraphicsData) {
 
-    for ref hex in hex_map.wrapper.into_iter() {
+    for hex in hex_map.iter() {
         let data = hex.borrow_data();
 
-        // let dest = tuple_to_point(gfx_data.offset);
-        let dest = tuple_to_point(ui_scaling(hex.to_pixel(DEFAULT_HEX_RADIUS).get(), gfx_data));
+        let dest = tuple_to_point(hex.to_pixel(gfx_data.layout).get());
 
         let hex_shape = &gfx_data.origin_mesh;
-        // let hex_shape = point_data_to_mesh(ctx, hex.to_pixels(DEFAULT_HEX_RADIUS));
+        // let hex_shape = point_data_to_mesh(ctx, hex.to_pixels(gfx_data.layout));
 
-        let scale = tuple_to_point((gfx_data.zoom * 0.95, gfx_data.zoom * 0.95));
+        let (size_x, size_y) = gfx_data.layout.size;
+        let scale = tuple_to_point((size_x / DEFAULT_HEX_RADIUS * 0.95, size_y / DEFAULT_HEX_RADIUS * 0.95));
 
         let color = match (data.selected, data.hovered) {
             (true, true) => Some(Color::from_rgb(255, 0, 0)),
@@ -103,8 +92,7 @@ impl ggez::event::EventHandler for State {
     fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: i32, y: i32) {
         println!("Mouse button pressed: {:?}, x: {}, y: {}", button, x, y);
         if let Ok(mouse_point) = mouse::get_position(ctx) {
-            let rel_point = reverse_ui_scaling(point_to_tuple(mouse_point), &self.gfx_data);
-            let result = self.map.mut_borrow_data_for_pixel(rel_point);
+            let result = self.map.mut_borrow_data_for_pixel(point_to_tuple(mouse_point), self.gfx_data.layout);
             match result {
                 Some(data) => data.selected = !data.selected,
                 None => ()
@@ -122,8 +110,7 @@ impl ggez::event::EventHandler for State {
         _yrel: i32,
     ) {
         self.map.mutate_all_data(&|d: &mut SomeData| d.hovered = false);
-        let rel_point = reverse_ui_scaling((x as f32, y as f32), &self.gfx_data);
-        let result = self.map.mut_borrow_data_for_pixel(rel_point);
+        let result = self.map.mut_borrow_data_for_pixel((x as f32, y as f32), self.gfx_data.layout);
         match result {
             Some(data) => data.hovered = true,
             None => ()
@@ -147,12 +134,15 @@ fn main() {
     
     let mut map = MyHexGrid::new(shape, None);
     let initializer = HexagonalCenteredGridIterator::new(5, shape.orient());
-    map.initialize(initializer, &SomeData::new);
+    map.initialize(initializer, &SomeData::new).unwrap();
     let origin_mesh = point_data_to_mesh(ctx, shape.corners((0.0, 0.0)));
 
     let mut state = State {
         map,
-        gfx_data: GraphicsData { zoom: 1.0, offset: (300.0, 300.0), origin_mesh}
+        gfx_data: GraphicsData {
+            layout: Layout::new(shape.orient(), (DEFAULT_HEX_RADIUS, DEFAULT_HEX_RADIUS), (300.0, 300.0)),
+            origin_mesh
+        }
     };
     event::run(ctx, &mut state).unwrap();
 }
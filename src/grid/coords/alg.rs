@@ -1,89 +1,804 @@
-use std::cmp::{max, min};
-
-fn sum_tuple_2((a, b): (i32, i32), (x, y): (i32, i32)) -> (i32, i32) {
-    (a + x, b + y)
-}
-
-fn sum_tuple_3((a, b, c): (i32, i32, i32), (x, y, z): (i32, i32, i32)) -> (i32, i32, i32) {
-    (a + x, b + y, c + z)
-}
-
-/*
-    Neighbors
-*/
-
-const CUBE_DIRECTIONS: [(i32, i32, i32); 6] = [
-    (1, -1, 0), (1, 0, -1), (0, 1, -1),
-    (-1, 1, 0), (-1, 0, 1), (0, -1, 1)
-];
-
-const AXIAL_DIRECTIONS: [(i32, i32); 6] = [
-    (1, 0), (1, -1), (0, -1),
-    (-1, 0), (-1, 1), (0, 1)
-];
-
-// Offset
-
-// fn get_neighbours_offset_odd(){}
-// fn get_neighbours_offset_even(){}
-
-// Doubled
-
-// fn get_neighbours_doubled(){}
-
-// Cube
-
-fn get_neighbour_cube(input: (i32, i32, i32), direction: usize) -> (i32, i32, i32) {
-    sum_tuple_3(input, CUBE_DIRECTIONS[direction % 6])
-}
-
-pub fn get_neighbours_cube(input: (i32, i32, i32)) -> [(i32, i32, i32); 6] {
-    [
-        get_neighbour_cube(input, 0),
-        get_neighbour_cube(input, 1),
-        get_neighbour_cube(input, 2),
-        get_neighbour_cube(input, 3),
-        get_neighbour_cube(input, 4),
-        get_neighbour_cube(input, 5)
-    ]
-}
-
-// Axial
-
-fn get_neighbour_axial(input: (i32, i32), direction: usize) -> (i32, i32) {
-    sum_tuple_2(input, AXIAL_DIRECTIONS[direction % 6])
-}
-
-pub fn get_neighbours_axial(input: (i32, i32)) -> [(i32, i32); 6] {
-    [
-        get_neighbour_axial(input, 0),
-        get_neighbour_axial(input, 1),
-        get_neighbour_axial(input, 2),
-        get_neighbour_axial(input, 3),
-        get_neighbour_axial(input, 4),
-        get_neighbour_axial(input, 5)
-    ]
-}
-
-/*
-    Distances
-*/
-
-
-/*
-    Ranges (All hexes within x distance from the argument)
-*/
-
-// Cube
-
-pub fn get_range_cube(input: (i32, i32, i32), range: i32) -> Vec<(i32, i32, i32)> {
-    let mut results = Vec::new();
-    let range = range.abs();
-
-    for x in -range..(range + 1) {
-        for y in max(-range, -range-x)..(min(range, range-x) + 1) {
-            results.push(sum_tuple_3(input, (x, y, -x-y)));
-        }
-    }
-    results
-}
+use std::cmp::{max, min};
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+
+use super::{convert, CoordType, CubeCoords};
+use super::num::{CoordInt, CoordFloat};
+use super::utils::{Orientation, offset_3d_tuple, scale_3d_tuple};
+
+fn sum_tuple_2<I: CoordInt>((a, b): (I, I), (x, y): (I, I)) -> (I, I) {
+    (a.plus(x), b.plus(y))
+}
+
+fn scale_tuple_3<I: CoordInt>((a, b, c): (I, I, I), factor: I) -> (I, I, I) {
+    (a.times(factor), b.times(factor), c.times(factor))
+}
+
+pub fn sub_tuple_3<I: CoordInt>((a, b, c): (I, I, I), (x, y, z): (I, I, I)) -> (I, I, I) {
+    (a.minus(x), b.minus(y), c.minus(z))
+}
+
+pub fn sum_tuple_3<I: CoordInt>((a, b, c): (I, I, I), (x, y, z): (I, I, I)) -> (I, I, I) {
+    (a.plus(x), b.plus(y), c.plus(z))
+}
+
+// Direction offsets in the integer type of the coordinate they're applied to
+fn step_2<I: CoordInt>((x, y): (i32, i32)) -> (I, I) {
+    (I::from_i8(x as i8), I::from_i8(y as i8))
+}
+
+fn step_3<I: CoordInt>((x, y, z): (i32, i32, i32)) -> (I, I, I) {
+    (I::from_i8(x as i8), I::from_i8(y as i8), I::from_i8(z as i8))
+}
+
+/*
+    Neighbors
+*/
+
+const CUBE_DIRECTIONS: [(i32, i32, i32); 6] = [
+    (1, -1, 0), (1, 0, -1), (0, 1, -1),
+    (-1, 1, 0), (-1, 0, 1), (0, -1, 1)
+];
+
+const AXIAL_DIRECTIONS: [(i32, i32); 6] = [
+    (1, 0), (1, -1), (0, -1),
+    (-1, 0), (-1, 1), (0, 1)
+];
+
+// Offset (Direction offsets depend on the parity of the shoved column/row)
+
+const FLAT_OFFSET_ODD_DIRECTIONS: [[(i32, i32); 6]; 2] = [
+    [(1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (0, 1)],
+    [(1, 1), (1, 0), (0, -1), (-1, 0), (-1, 1), (0, 1)]
+];
+
+const FLAT_OFFSET_EVEN_DIRECTIONS: [[(i32, i32); 6]; 2] = [
+    [(1, 1), (1, 0), (0, -1), (-1, 0), (-1, 1), (0, 1)],
+    [(1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (0, 1)]
+];
+
+const POINTY_OFFSET_ODD_DIRECTIONS: [[(i32, i32); 6]; 2] = [
+    [(1, 0), (0, -1), (-1, -1), (-1, 0), (-1, 1), (0, 1)],
+    [(1, 0), (1, -1), (0, -1), (-1, 0), (0, 1), (1, 1)]
+];
+
+const POINTY_OFFSET_EVEN_DIRECTIONS: [[(i32, i32); 6]; 2] = [
+    [(1, 0), (1, -1), (0, -1), (-1, 0), (0, 1), (1, 1)],
+    [(1, 0), (0, -1), (-1, -1), (-1, 0), (-1, 1), (0, 1)]
+];
+
+fn offset_direction<I: CoordInt>(table: &[[(i32, i32); 6]; 2], parity: I, direction: usize) -> (I, I) {
+    step_2(table[parity.is_odd() as usize][direction % 6])
+}
+
+pub fn get_neighbour_offset_odd<I: CoordInt>((x, y): (I, I), direction: usize, orientation: Orientation) -> (I, I) {
+    let offset = match orientation {
+        Orientation::Flat => offset_direction(&FLAT_OFFSET_ODD_DIRECTIONS, x, direction),
+        Orientation::Pointy => offset_direction(&POINTY_OFFSET_ODD_DIRECTIONS, y, direction)
+    };
+    sum_tuple_2((x, y), offset)
+}
+
+pub fn get_neighbours_offset_odd<I: CoordInt>(input: (I, I), orientation: Orientation) -> [(I, I); 6] {
+    [
+        get_neighbour_offset_odd(input, 0, orientation),
+        get_neighbour_offset_odd(input, 1, orientation),
+        get_neighbour_offset_odd(input, 2, orientation),
+        get_neighbour_offset_odd(input, 3, orientation),
+        get_neighbour_offset_odd(input, 4, orientation),
+        get_neighbour_offset_odd(input, 5, orientation)
+    ]
+}
+
+pub fn get_neighbour_offset_even<I: CoordInt>((x, y): (I, I), direction: usize, orientation: Orientation) -> (I, I) {
+    let offset = match orientation {
+        Orientation::Flat => offset_direction(&FLAT_OFFSET_EVEN_DIRECTIONS, x, direction),
+        Orientation::Pointy => offset_direction(&POINTY_OFFSET_EVEN_DIRECTIONS, y, direction)
+    };
+    sum_tuple_2((x, y), offset)
+}
+
+pub fn get_neighbours_offset_even<I: CoordInt>(input: (I, I), orientation: Orientation) -> [(I, I); 6] {
+    [
+        get_neighbour_offset_even(input, 0, orientation),
+        get_neighbour_offset_even(input, 1, orientation),
+        get_neighbour_offset_even(input, 2, orientation),
+        get_neighbour_offset_even(input, 3, orientation),
+        get_neighbour_offset_even(input, 4, orientation),
+        get_neighbour_offset_even(input, 5, orientation)
+    ]
+}
+
+// Doubled
+
+const FLAT_DOUBLED_DIRECTIONS: [(i32, i32); 6] = [
+    (1, 1), (1, -1), (0, -2),
+    (-1, -1), (-1, 1), (0, 2)
+];
+
+const POINTY_DOUBLED_DIRECTIONS: [(i32, i32); 6] = [
+    (2, 0), (1, -1), (-1, -1),
+    (-2, 0), (-1, 1), (1, 1)
+];
+
+pub fn get_neighbour_doubled<I: CoordInt>(input: (I, I), direction: usize, orientation: Orientation) -> (I, I) {
+    match orientation {
+        Orientation::Flat => sum_tuple_2(input, step_2(FLAT_DOUBLED_DIRECTIONS[direction % 6])),
+        Orientation::Pointy => sum_tuple_2(input, step_2(POINTY_DOUBLED_DIRECTIONS[direction % 6]))
+    }
+}
+
+pub fn get_neighbours_doubled<I: CoordInt>(input: (I, I), orientation: Orientation) -> [(I, I); 6] {
+    [
+        get_neighbour_doubled(input, 0, orientation),
+        get_neighbour_doubled(input, 1, orientation),
+        get_neighbour_doubled(input, 2, orientation),
+        get_neighbour_doubled(input, 3, orientation),
+        get_neighbour_doubled(input, 4, orientation),
+        get_neighbour_doubled(input, 5, orientation)
+    ]
+}
+
+// Cube
+
+pub fn get_neighbour_cube<I: CoordInt>(input: (I, I, I), direction: usize) -> (I, I, I) {
+    sum_tuple_3(input, step_3(CUBE_DIRECTIONS[direction % 6]))
+}
+
+pub fn get_neighbour_cube_orient<I: CoordInt>(input: (I, I, I), direction: usize, _o: Orientation) -> (I, I, I) {
+    get_neighbour_cube(input, direction)
+}
+
+pub fn get_neighbours_cube<I: CoordInt>(input: (I, I, I)) -> [(I, I, I); 6] {
+    [
+        get_neighbour_cube(input, 0),
+        get_neighbour_cube(input, 1),
+        get_neighbour_cube(input, 2),
+        get_neighbour_cube(input, 3),
+        get_neighbour_cube(input, 4),
+        get_neighbour_cube(input, 5)
+    ]
+}
+
+// Axial
+
+pub fn get_neighbour_axial<I: CoordInt>(input: (I, I), direction: usize) -> (I, I) {
+    sum_tuple_2(input, step_2(AXIAL_DIRECTIONS[direction % 6]))
+}
+
+pub fn get_neighbour_axial_orient<I: CoordInt>(input: (I, I), direction: usize, _o: Orientation) -> (I, I) {
+    get_neighbour_axial(input, direction)
+}
+
+pub fn get_neighbours_axial<I: CoordInt>(input: (I, I)) -> [(I, I); 6] {
+    [
+        get_neighbour_axial(input, 0),
+        get_neighbour_axial(input, 1),
+        get_neighbour_axial(input, 2),
+        get_neighbour_axial(input, 3),
+        get_neighbour_axial(input, 4),
+        get_neighbour_axial(input, 5)
+    ]
+}
+
+// Index of the neighbour direction matching a cube offset, if the offset is a single step
+pub fn cube_direction_index<I: CoordInt>(offset: (I, I, I)) -> Option<usize> {
+    CUBE_DIRECTIONS.iter().position(|d| step_3::<I>(*d) == offset)
+}
+
+/*
+    Diagonals (Hexes sharing a single vertex, two steps away)
+*/
+
+const CUBE_DIAGONALS: [(i32, i32, i32); 6] = [
+    (2, -1, -1), (1, 1, -2), (-1, 2, -1),
+    (-2, 1, 1), (-1, -1, 2), (1, -2, 1)
+];
+
+pub fn get_diagonal_cube<I: CoordInt>(input: (I, I, I), diagonal: usize) -> (I, I, I) {
+    sum_tuple_3(input, step_3(CUBE_DIAGONALS[diagonal % 6]))
+}
+
+pub fn get_diagonals_cube<I: CoordInt>(input: (I, I, I)) -> [(I, I, I); 6] {
+    [
+        get_diagonal_cube(input, 0),
+        get_diagonal_cube(input, 1),
+        get_diagonal_cube(input, 2),
+        get_diagonal_cube(input, 3),
+        get_diagonal_cube(input, 4),
+        get_diagonal_cube(input, 5)
+    ]
+}
+
+// Index of the diagonal matching a cube offset, if the offset is a single diagonal step
+pub fn cube_diagonal_index<I: CoordInt>(offset: (I, I, I)) -> Option<usize> {
+    CUBE_DIAGONALS.iter().position(|d| step_3::<I>(*d) == offset)
+}
+
+/*
+    Distances
+*/
+
+// Offset
+
+pub fn distance_offset_odd<I: CoordInt>(a: (I, I), b: (I, I), orientation: Orientation) -> I {
+    distance_cube(convert::offset_odd_to_cube(a, orientation), convert::offset_odd_to_cube(b, orientation))
+}
+
+pub fn distance_offset_even<I: CoordInt>(a: (I, I), b: (I, I), orientation: Orientation) -> I {
+    distance_cube(convert::offset_even_to_cube(a, orientation), convert::offset_even_to_cube(b, orientation))
+}
+
+// Doubled
+
+pub fn distance_doubled<I: CoordInt>((ax, ay): (I, I), (bx, by): (I, I), orientation: Orientation) -> I {
+    let (dx, dy) = (ax.minus(bx).magnitude(), ay.minus(by).magnitude());
+    match orientation {
+        Orientation::Flat => dx.plus(max(I::ZERO, dy.minus(dx).half())),
+        Orientation::Pointy => dy.plus(max(I::ZERO, dx.minus(dy).half()))
+    }
+}
+
+// Cube
+
+pub fn distance_cube<I: CoordInt>((ax, ay, az): (I, I, I), (bx, by, bz): (I, I, I)) -> I {
+    max(ax.minus(bx).magnitude(), max(ay.minus(by).magnitude(), az.minus(bz).magnitude()))
+}
+
+pub fn distance_cube_orient<I: CoordInt>(a: (I, I, I), b: (I, I, I), _o: Orientation) -> I {
+    distance_cube(a, b)
+}
+
+// Axial
+
+pub fn distance_axial<I: CoordInt>(a: (I, I), b: (I, I)) -> I {
+    distance_cube(convert::axial_to_cube(a), convert::axial_to_cube(b))
+}
+
+pub fn distance_axial_orient<I: CoordInt>(a: (I, I), b: (I, I), _o: Orientation) -> I {
+    distance_axial(a, b)
+}
+
+/*
+    Lines (All hexes on a straight line between two hexes, inclusive)
+*/
+
+// Nudge applied to both endpoints so that lines running exactly along hex
+// edges consistently round to the same side instead of flickering between both
+const LINE_EPSILON: (f64, f64, f64) = (1e-6, 2e-6, -3e-6);
+
+fn lerp<F: CoordFloat>(a: F, b: F, t: F) -> F {
+    a + (b - a) * t
+}
+
+pub fn lerp_cube<F: CoordFloat>((ax, ay, az): (F, F, F), (bx, by, bz): (F, F, F), t: F) -> (F, F, F) {
+    (lerp(ax, bx, t), lerp(ay, by, t), lerp(az, bz, t))
+}
+
+fn cube_to_float<I: CoordInt>((x, y, z): (I, I, I)) -> (f64, f64, f64) {
+    (x.to_float(), y.to_float(), z.to_float())
+}
+
+// Cube
+
+pub fn line_cube<I: CoordInt>(a: (I, I, I), b: (I, I, I)) -> Vec<(I, I, I)> {
+    let n = distance_cube(a, b).to_i128();
+    if n == 0 {
+        return vec![a]
+    }
+    // Steps are taken along the offset from `a` (and `a` added back after rounding) so the
+    // nudge isn't lost to float precision however far the line is from the origin
+    let delta = cube_to_float(sub_tuple_3(b, a));
+    let step = 1.0 / n as f64;
+    (0..(n + 1))
+        .map(|i| offset_3d_tuple(scale_3d_tuple(delta, step * i as f64), LINE_EPSILON))
+        .map(|offset| sum_tuple_3(a, convert::cube_round(offset)))
+        .collect()
+}
+
+// Any coordinate system (the orientation of the first coordinate is used)
+
+pub fn line<C: CoordType>(a: &C, b: &C) -> Vec<C> {
+    let orientation = a.orientation();
+    line_cube(a.to_cube(), b.to_cube()).into_iter().map(|t| C::from_cube(t, orientation)).collect()
+}
+
+/*
+    Ranges (All hexes within x distance from the argument)
+*/
+
+// Cube
+
+pub fn get_range_cube<I: CoordInt>(input: (I, I, I), range: I) -> Vec<(I, I, I)> {
+    RangeIterator::<CubeCoords<I>>::new_cube(input, range, Orientation::Flat).map(|c| c.get()).collect()
+}
+
+// Lazily walks the hexes row by row (by cube z), and along each row by increasing cube x
+
+pub struct RangeIterator<C: CoordType> {
+    orientation: Orientation,
+    center: (C::Int, C::Int, C::Int),
+    radius: C::Int,
+    x: C::Int,
+    z: C::Int,
+    remaining: usize,
+    _c: PhantomData<C>
+}
+
+impl<C: CoordType> RangeIterator<C> {
+    fn new_cube(center: (C::Int, C::Int, C::Int), radius: C::Int, orientation: Orientation) -> Self {
+        let radius = radius.magnitude();
+        Self {
+            orientation,
+            center,
+            radius,
+            x: C::Int::ZERO,
+            z: radius.negate(),
+            remaining: range_size(radius),
+            _c: PhantomData
+        }
+    }
+}
+
+impl<C: CoordType> Iterator for RangeIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None
+        }
+        let (x, z) = (self.x, self.z);
+        self.remaining -= 1;
+        // Nothing is left to step to after the last hex, so don't (the next row may not fit the type)
+        if self.remaining > 0 {
+            if x >= min(self.radius, self.radius.minus(z)) {
+                self.z = z.plus(C::Int::ONE);
+                self.x = max(self.radius.negate(), self.radius.negate().minus(self.z));
+            } else {
+                self.x = x.plus(C::Int::ONE);
+            }
+        }
+        Some(C::from_cube(sum_tuple_3(self.center, (x, x.negate().minus(z), z)), self.orientation))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<C: CoordType> ExactSizeIterator for RangeIterator<C> {}
+
+pub fn range<C: CoordType>(center: &C, radius: C::Int) -> RangeIterator<C> {
+    RangeIterator::new_cube(center.to_cube(), radius, center.orientation())
+}
+
+/*
+    Rotation (In steps of 60 degrees around a center hex, positive steps rotate clockwise)
+*/
+
+// Cube
+
+pub fn rotate_cube<I: CoordInt>(input: (I, I, I), center: (I, I, I), steps: i32) -> (I, I, I) {
+    let (mut x, mut y, mut z) = sub_tuple_3(input, center);
+    for _ in 0..steps.rem_euclid(6) {
+        let (rx, ry, rz) = (z.negate(), x.negate(), y.negate());
+        x = rx;
+        y = ry;
+        z = rz;
+    }
+    sum_tuple_3((x, y, z), center)
+}
+
+/*
+    Reflection (Across an axis running through a center hex)
+
+    The axes are named in axial terms, in cube coordinates q is x, r is z and s is y
+*/
+
+// Cube
+
+pub fn reflect_cube_q<I: CoordInt>(input: (I, I, I), center: (I, I, I)) -> (I, I, I) {
+    let (x, y, z) = sub_tuple_3(input, center);
+    sum_tuple_3((x, z, y), center)
+}
+
+pub fn reflect_cube_r<I: CoordInt>(input: (I, I, I), center: (I, I, I)) -> (I, I, I) {
+    let (x, y, z) = sub_tuple_3(input, center);
+    sum_tuple_3((y, x, z), center)
+}
+
+pub fn reflect_cube_s<I: CoordInt>(input: (I, I, I), center: (I, I, I)) -> (I, I, I) {
+    let (x, y, z) = sub_tuple_3(input, center);
+    sum_tuple_3((z, y, x), center)
+}
+
+/*
+    Rings (All hexes at exactly x distance from the argument)
+*/
+
+// Number of hexes within a range of a center hex (inclusive), as many as fit in a `usize`
+fn range_size<I: CoordInt>(range: I) -> usize {
+    let range = range.to_i128().unsigned_abs();
+    usize::try_from(3 * range * (range + 1) + 1).unwrap_or(usize::MAX)
+}
+
+fn ring_size<I: CoordInt>(radius: I) -> usize {
+    match radius.to_i128().unsigned_abs() {
+        0 => 1,
+        r => usize::try_from(6 * r).unwrap_or(usize::MAX)
+    }
+}
+
+pub struct RingIterator<C: CoordType> {
+    orientation: Orientation,
+    current: (C::Int, C::Int, C::Int),
+    radius: C::Int,
+    side: usize,
+    step: C::Int,
+    remaining: usize,
+    _c: PhantomData<C>
+}
+
+impl<C: CoordType> RingIterator<C> {
+    fn new_cube(center: (C::Int, C::Int, C::Int), radius: C::Int, orientation: Orientation) -> Self {
+        let radius = radius.magnitude();
+        Self {
+            orientation,
+            current: sum_tuple_3(center, scale_tuple_3(step_3(CUBE_DIRECTIONS[4]), radius)),
+            radius,
+            side: 0,
+            step: C::Int::ZERO,
+            remaining: ring_size(radius),
+            _c: PhantomData
+        }
+    }
+}
+
+impl<C: CoordType> Iterator for RingIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None
+        }
+        let result = self.current;
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.current = get_neighbour_cube(self.current, self.side);
+            self.step = self.step.plus(C::Int::ONE);
+            if self.step >= self.radius {
+                self.step = C::Int::ZERO;
+                self.side += 1;
+            }
+        }
+        Some(C::from_cube(result, self.orientation))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<C: CoordType> ExactSizeIterator for RingIterator<C> {}
+
+pub fn ring<C: CoordType>(center: &C, radius: C::Int) -> RingIterator<C> {
+    RingIterator::new_cube(center.to_cube(), radius, center.orientation())
+}
+
+/*
+    Spirals (All hexes within x distance from the argument, walked ring by ring outwards)
+*/
+
+pub struct SpiralIterator<C: CoordType> {
+    center: (C::Int, C::Int, C::Int),
+    orientation: Orientation,
+    radius: C::Int,
+    ring_radius: C::Int,
+    ring: RingIterator<C>
+}
+
+impl<C: CoordType> SpiralIterator<C> {
+    fn new_cube(center: (C::Int, C::Int, C::Int), radius: C::Int, orientation: Orientation) -> Self {
+        Self {
+            center,
+            orientation,
+            radius: radius.magnitude(),
+            ring_radius: C::Int::ZERO,
+            ring: RingIterator::new_cube(center, C::Int::ZERO, orientation)
+        }
+    }
+}
+
+impl<C: CoordType> Iterator for SpiralIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.ring.next() {
+                return Some(result)
+            }
+            if self.ring_radius >= self.radius {
+                return None
+            }
+            self.ring_radius = self.ring_radius.plus(C::Int::ONE);
+            self.ring = RingIterator::new_cube(self.center, self.ring_radius, self.orientation);
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.ring.len() + range_size(self.radius) - range_size(self.ring_radius);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<C: CoordType> ExactSizeIterator for SpiralIterator<C> {}
+
+pub fn spiral<C: CoordType>(center: &C, radius: C::Int) -> SpiralIterator<C> {
+    SpiralIterator::new_cube(center.to_cube(), radius, center.orientation())
+}
+
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::{max, min};
+    use std::collections::HashSet;
+
+    // Every hex within `n` of `center`, found independently of the range iterator
+    fn hexes_within((cx, cy, cz): (i32, i32, i32), n: i32) -> HashSet<(i32, i32, i32)> {
+        let mut result = HashSet::new();
+        for dx in -n..=n {
+            for dy in max(-n, -dx - n)..=min(n, -dx + n) {
+                result.insert((cx + dx, cy + dy, cz - dx - dy));
+            }
+        }
+        result
+    }
+
+    /*
+        Neighbour Tests
+    */
+
+    #[test]
+    fn test_neighbours_match_cube() {
+        let orientations = [Orientation::Flat, Orientation::Pointy];
+        for orient in orientations.iter().cloned() {
+            for x in -3..4 {
+                for y in -3..4 {
+                    let odd_cube = convert::offset_odd_to_cube((x, y), orient);
+                    let even_cube = convert::offset_even_to_cube((x, y), orient);
+                    let doubled = convert::cube_to_doubled(odd_cube, orient);
+                    for dir in 0..6 {
+                        assert_eq!(
+                            convert::offset_odd_to_cube(get_neighbour_offset_odd((x, y), dir, orient), orient),
+                            get_neighbour_cube(odd_cube, dir));
+                        assert_eq!(
+                            convert::offset_even_to_cube(get_neighbour_offset_even((x, y), dir, orient), orient),
+                            get_neighbour_cube(even_cube, dir));
+                        assert_eq!(
+                            convert::doubled_to_cube(get_neighbour_doubled(doubled, dir, orient), orient),
+                            get_neighbour_cube(odd_cube, dir));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_neighbours_offset() {
+        assert_eq!(get_neighbours_offset_odd((2, 2), Orientation::Flat),
+            [(3, 2), (3, 1), (2, 1), (1, 1), (1, 2), (2, 3)]);
+        assert_eq!(get_neighbours_offset_odd((1, 2), Orientation::Flat),
+            [(2, 3), (2, 2), (1, 1), (0, 2), (0, 3), (1, 3)]);
+        assert_eq!(get_neighbours_offset_even((2, 1), Orientation::Pointy),
+            [(3, 1), (2, 0), (1, 0), (1, 1), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_diagonals_cube() {
+        for (i, diagonal) in get_diagonals_cube((1, -1, 0)).iter().enumerate() {
+            assert_eq!(distance_cube((1, -1, 0), *diagonal), 2);
+            // A diagonal sits between two adjacent neighbours
+            let (a, b) = (get_neighbour_cube((1, -1, 0), i), get_neighbour_cube((1, -1, 0), i + 1));
+            assert_eq!(distance_cube(a, *diagonal), 1);
+            assert_eq!(distance_cube(b, *diagonal), 1);
+        }
+        assert_eq!(cube_diagonal_index((-2, 1, 1)), Some(3));
+        assert_eq!(cube_diagonal_index((1, -1, 0)), None);
+        assert_eq!(cube_direction_index((1, -1, 0)), Some(0));
+    }
+
+    /*
+        Distance Tests
+    */
+
+    #[test]
+    fn test_distance_cube() {
+        assert_eq!(distance_cube((0, 0, 0), (0, 0, 0)), 0);
+        assert_eq!(distance_cube((0, 0, 0), (1, -1, 0)), 1);
+        assert_eq!(distance_cube((0, 0, 0), (3, -1, -2)), 3);
+        assert_eq!(distance_cube((2, -3, 1), (-1, 2, -1)), 5);
+    }
+
+    #[test]
+    fn test_distance_axial() {
+        assert_eq!(distance_axial((0, 0), (0, 0)), 0);
+        assert_eq!(distance_axial((0, 0), (-1, 1)), 1);
+        assert_eq!(distance_axial((2, 1), (-1, -1)), 5);
+    }
+
+    #[test]
+    fn test_distance_doubled() {
+        assert_eq!(distance_doubled((0, 0), (0, 2), Orientation::Flat), 1);
+        assert_eq!(distance_doubled((0, 0), (1, 1), Orientation::Flat), 1);
+        assert_eq!(distance_doubled((0, 0), (2, 4), Orientation::Flat), 3);
+        assert_eq!(distance_doubled((0, 0), (3, 1), Orientation::Flat), 3);
+
+        assert_eq!(distance_doubled((0, 0), (2, 0), Orientation::Pointy), 1);
+        assert_eq!(distance_doubled((0, 0), (1, 1), Orientation::Pointy), 1);
+        assert_eq!(distance_doubled((0, 0), (4, 2), Orientation::Pointy), 3);
+        assert_eq!(distance_doubled((0, 0), (1, 3), Orientation::Pointy), 3);
+    }
+
+    #[test]
+    fn test_distance_offset() {
+        assert_eq!(distance_offset_odd((0, 0), (3, 1), Orientation::Flat), 3);
+        assert_eq!(distance_offset_odd((0, 0), (2, 2), Orientation::Flat), 3);
+        assert_eq!(distance_offset_odd((0, 0), (1, 3), Orientation::Pointy), 3);
+
+        assert_eq!(distance_offset_even((0, 0), (3, 2), Orientation::Flat), 3);
+        assert_eq!(distance_offset_even((0, 0), (2, 2), Orientation::Flat), 3);
+        assert_eq!(distance_offset_even((0, 0), (2, 3), Orientation::Pointy), 3);
+    }
+
+    /*
+        Line Tests
+    */
+
+    #[test]
+    fn test_line_cube() {
+        assert_eq!(line_cube((0, 0, 0), (0, 0, 0)), vec![(0, 0, 0)]);
+        assert_eq!(line_cube((0, 0, 0), (3, -3, 0)), vec![(0, 0, 0), (1, -1, 0), (2, -2, 0), (3, -3, 0)]);
+        assert_eq!(line_cube((0, 0, 0), (-2, 0, 2)), vec![(0, 0, 0), (-1, 0, 1), (-2, 0, 2)]);
+    }
+
+    #[test]
+    fn test_line_cube_along_edge() {
+        // (0,0,0) -> (2,-1,-1) runs exactly between (1,-1,0) and (1,0,-1)
+        let line = line_cube((0, 0, 0), (2, -1, -1));
+        assert_eq!(line, vec![(0, 0, 0), (1, 0, -1), (2, -1, -1)]);
+
+        // The nudge picks the same side wherever the line is on the map
+        assert_eq!(line_cube((0, 0, 0), (1, 1, -2)), vec![(0, 0, 0), (0, 1, -1), (1, 1, -2)]);
+        for origin in [(100, -100, 0), (-5000, 2500, 2500), (1_000_000, 0, -1_000_000)].iter() {
+            let translate = |c: (i32, i32, i32)| sum_tuple_3(c, *origin);
+            let far = line_cube(translate((0, 0, 0)), translate((1, 1, -2)));
+            assert_eq!(far, vec![translate((0, 0, 0)), translate((0, 1, -1)), translate((1, 1, -2))]);
+            let far = line_cube(translate((0, 0, 0)), translate((2, -1, -1)));
+            assert_eq!(far[1], translate((1, 0, -1)));
+        }
+    }
+
+    #[test]
+    fn test_line_generic() {
+        use super::super::OffsetOddCoords;
+        let a = OffsetOddCoords::new((0, 0), Orientation::Flat);
+        let b = OffsetOddCoords::new((4, 1), Orientation::Flat);
+        let line = line(&a, &b);
+        assert_eq!(line.len() as i32, a.distance(&b) + 1);
+        assert_eq!(line[0].get(), (0, 0));
+        assert_eq!(line[line.len() - 1].get(), (4, 1));
+    }
+
+    /*
+        Rotation/Reflection Tests
+    */
+
+    #[test]
+    fn test_rotate_cube() {
+        // Neighbour directions are ordered counter-clockwise, so clockwise steps go backwards
+        for i in 0..6 {
+            assert_eq!(rotate_cube(CUBE_DIRECTIONS[i], (0, 0, 0), 1), CUBE_DIRECTIONS[(i + 5) % 6]);
+            assert_eq!(rotate_cube(CUBE_DIRECTIONS[i], (0, 0, 0), -1), CUBE_DIRECTIONS[(i + 1) % 6]);
+        }
+        assert_eq!(rotate_cube((3, -1, -2), (1, -1, 0), 6), (3, -1, -2));
+        assert_eq!(rotate_cube((3, -1, -2), (1, -1, 0), 3), (-1, -1, 2));
+        assert_eq!(rotate_cube((3, -1, -2), (1, -1, 0), 2), rotate_cube((3, -1, -2), (1, -1, 0), -4));
+        assert_eq!(rotate_cube((1, -1, 0), (1, -1, 0), 1), (1, -1, 0));
+    }
+
+    #[test]
+    fn test_reflect_cube() {
+        assert_eq!(reflect_cube_q((1, -3, 2), (0, 0, 0)), (1, 2, -3));
+        assert_eq!(reflect_cube_r((1, -3, 2), (0, 0, 0)), (-3, 1, 2));
+        assert_eq!(reflect_cube_s((1, -3, 2), (0, 0, 0)), (2, -3, 1));
+
+        let center = (2, -1, -1);
+        let input = (4, -5, 1);
+        for f in [reflect_cube_q, reflect_cube_r, reflect_cube_s].iter() {
+            let reflected = f(input, center);
+            assert_eq!(distance_cube(reflected, center), distance_cube(input, center));
+            assert_eq!(f(reflected, center), input);
+        }
+    }
+
+    /*
+        Ring/Spiral Tests
+    */
+
+    #[test]
+    fn test_ring() {
+        use super::super::CubeCoords;
+        let center = CubeCoords::new((1, -2, 1), Orientation::Flat);
+
+        let zero: Vec<(i32, i32, i32)> = ring(&center, 0).map(|c| c.get()).collect();
+        assert_eq!(zero, vec![(1, -2, 1)]);
+
+        for radius in 1..5 {
+            let hexes: Vec<(i32, i32, i32)> = ring(&center, radius).map(|c| c.get()).collect();
+            assert_eq!(hexes.len(), 6 * radius as usize);
+            for hex in hexes.iter() {
+                assert_eq!(distance_cube(center.get(), *hex), radius);
+            }
+            for pair in hexes.windows(2) {
+                assert_eq!(distance_cube(pair[0], pair[1]), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_spiral() {
+        use super::super::AxialCoords;
+        let center = AxialCoords::new((2, -1), Orientation::Pointy);
+        let iter = spiral(&center, 3);
+        assert_eq!(iter.len(), 37);
+
+        let hexes: Vec<(i32, i32)> = iter.map(|c| c.get()).collect();
+        assert_eq!(hexes.len(), 37);
+        assert_eq!(hexes[0], (2, -1));
+
+        let found: HashSet<(i32, i32, i32)> = hexes.into_iter().map(convert::axial_to_cube).collect();
+        assert_eq!(found.len(), 37);
+        assert_eq!(found, hexes_within(convert::axial_to_cube((2, -1)), 3));
+    }
+
+    #[test]
+    fn test_range() {
+        use super::super::CubeCoords;
+        let center = CubeCoords::new((1, -2, 1), Orientation::Flat);
+        assert_eq!(range(&center, 0).map(|c| c.get()).collect::<Vec<_>>(), vec![(1, -2, 1)]);
+
+        for radius in 1..5 {
+            let mut iter = range(&center, radius);
+            assert_eq!(iter.len(), 3 * (radius * (radius + 1)) as usize + 1);
+            iter.next();
+            assert_eq!(iter.len(), 3 * (radius * (radius + 1)) as usize);
+
+            let hexes: Vec<(i32, i32, i32)> = range(&center, radius).map(|c| c.get()).collect();
+            // Row by row, left to right
+            let rows: Vec<(i32, i32)> = hexes.iter().map(|h| (h.2, h.0)).collect();
+            let mut sorted = rows.clone();
+            sorted.sort();
+            assert_eq!(rows, sorted);
+
+            let expected = hexes_within(center.get(), radius);
+            let found: HashSet<(i32, i32, i32)> = hexes.iter().cloned().collect();
+            assert_eq!(found.len(), 3 * (radius * (radius + 1)) as usize + 1);
+            assert!(found.iter().all(|h| distance_cube(center.get(), *h) <= radius));
+            assert_eq!(found, expected);
+            assert_eq!(get_range_cube(center.get(), radius).into_iter().collect::<HashSet<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_lerp_cube() {
+        assert_eq!(lerp_cube((0.0f32, 0.0, 0.0), (4.0, -4.0, 0.0), 0.25), (1.0, -1.0, 0.0));
+        // Far from the origin positions need the precision of f64
+        let far = 3_000_000_000.0f64;
+        assert_eq!(lerp_cube((far, -far, 0.0), (far + 2.0, -far, -2.0), 0.5), (far + 1.0, -far, -1.0));
+    }
+}
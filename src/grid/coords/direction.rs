@@ -0,0 +1,231 @@
+use std::ops::{Mul, Neg};
+
+use super::{alg, CoordType};
+use super::num::CoordInt;
+use super::utils::Orientation;
+
+/*
+    Compass directions, named as they appear on screen (pixel y increases downwards)
+
+    Which names are valid depends on the orientation of the hex:
+        Flat:   edges face N/NE/SE/S/SW/NW, corners (diagonals) face E/NE/NW/W/SW/SE
+        Pointy: edges face E/NE/NW/W/SW/SE, corners (diagonals) face N/NE/NW/S/SE/SW
+
+    Directions are indexed in the same order as the neighbour functions in `alg`,
+    Diagonal i sits between neighbour directions i and i + 1.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction { N, NE, E, SE, S, SW, W, NW }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Diagonal { N, NE, E, SE, S, SW, W, NW }
+
+const FLAT_DIRECTIONS: [Direction; 6] = [
+    Direction::SE, Direction::NE, Direction::N,
+    Direction::NW, Direction::SW, Direction::S
+];
+
+const POINTY_DIRECTIONS: [Direction; 6] = [
+    Direction::E, Direction::NE, Direction::NW,
+    Direction::W, Direction::SW, Direction::SE
+];
+
+const FLAT_DIAGONALS: [Diagonal; 6] = [
+    Diagonal::E, Diagonal::NE, Diagonal::NW,
+    Diagonal::W, Diagonal::SW, Diagonal::SE
+];
+
+const POINTY_DIAGONALS: [Diagonal; 6] = [
+    Diagonal::NE, Diagonal::N, Diagonal::NW,
+    Diagonal::SW, Diagonal::S, Diagonal::SE
+];
+
+macro_rules! impl_compass_type {
+    ($type:ident, $flat:ident, $pointy:ident, $index_fn:ident) => {
+        impl $type {
+            // All names in clockwise order, starting from N
+            const COMPASS: [$type; 8] = [
+                $type::N, $type::NE, $type::E, $type::SE,
+                $type::S, $type::SW, $type::W, $type::NW
+            ];
+
+            pub fn all(orientation: Orientation) -> [Self; 6] {
+                match orientation {
+                    Orientation::Flat => $flat,
+                    Orientation::Pointy => $pointy
+                }
+            }
+
+            pub fn from_index(index: usize, orientation: Orientation) -> Self {
+                Self::all(orientation)[index % 6]
+            }
+
+            pub fn index(self, orientation: Orientation) -> Option<usize> {
+                Self::all(orientation).iter().position(|d| *d == self)
+            }
+
+            pub fn is_valid(self, orientation: Orientation) -> bool {
+                self.index(orientation).is_some()
+            }
+
+            pub fn opposite(self) -> Self {
+                Self::COMPASS[(self as usize + 4) % 8]
+            }
+
+            // Rotating a name that isn't valid for the orientation snaps to the next valid one
+            pub fn rotate_cw(self, orientation: Orientation) -> Self {
+                self.walk_compass(1, orientation)
+            }
+
+            pub fn rotate_ccw(self, orientation: Orientation) -> Self {
+                self.walk_compass(7, orientation)
+            }
+
+            fn walk_compass(self, step: usize, orientation: Orientation) -> Self {
+                let mut i = self as usize;
+                loop {
+                    i = (i + step) % 8;
+                    if Self::COMPASS[i].is_valid(orientation) {
+                        return Self::COMPASS[i]
+                    }
+                }
+            }
+
+            pub fn between<C: CoordType>(from: &C, to: &C) -> Option<Self> {
+                alg::$index_fn(alg::sub_tuple_3(to.to_cube(), from.to_cube()))
+                    .map(|i| Self::from_index(i, from.orientation()))
+            }
+        }
+    }
+}
+
+impl_compass_type!(Direction, FLAT_DIRECTIONS, POINTY_DIRECTIONS, cube_direction_index);
+impl_compass_type!(Diagonal, FLAT_DIAGONALS, POINTY_DIAGONALS, cube_diagonal_index);
+
+/*
+    Offset of a number of steps in a direction, built with `Direction * i32`
+
+    The offset only becomes a concrete cube offset once an orientation is known,
+    so it can be added to any coordinate the direction is valid for. Step counts that
+    overflow panic, like the coordinate arithmetic in `ops`.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DirectionVector { pub direction: Direction, pub steps: i32 }
+
+impl DirectionVector {
+    pub fn new(direction: Direction, steps: i32) -> Self {
+        Self { direction, steps }
+    }
+
+    pub fn to_cube(self, orientation: Orientation) -> Option<(i32, i32, i32)> {
+        self.direction.index(orientation).map(|i| {
+            let (x, y, z) = alg::get_neighbour_cube((0, 0, 0), i);
+            (x.times(self.steps), y.times(self.steps), z.times(self.steps))
+        })
+    }
+}
+
+impl Mul<i32> for Direction {
+    type Output = DirectionVector;
+
+    fn mul(self, steps: i32) -> DirectionVector {
+        DirectionVector::new(self, steps)
+    }
+}
+
+impl Mul<i32> for DirectionVector {
+    type Output = DirectionVector;
+
+    fn mul(self, factor: i32) -> DirectionVector {
+        DirectionVector::new(self.direction, self.steps.times(factor))
+    }
+}
+
+impl Neg for DirectionVector {
+    type Output = DirectionVector;
+
+    fn neg(self) -> DirectionVector {
+        DirectionVector::new(self.direction, self.steps.negate())
+    }
+}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{CubeCoords, OffsetOddCoords};
+
+    #[test]
+    fn test_rotation() {
+        assert_eq!(Direction::N.rotate_cw(Orientation::Flat), Direction::NE);
+        assert_eq!(Direction::NE.rotate_cw(Orientation::Flat), Direction::SE);
+        assert_eq!(Direction::NE.rotate_cw(Orientation::Pointy), Direction::E);
+        assert_eq!(Direction::NE.rotate_ccw(Orientation::Pointy), Direction::NW);
+        assert_eq!(Diagonal::NE.rotate_cw(Orientation::Flat), Diagonal::E);
+        assert_eq!(Diagonal::SE.rotate_cw(Orientation::Pointy), Diagonal::S);
+
+        for orient in [Orientation::Flat, Orientation::Pointy].iter().cloned() {
+            for d in Direction::all(orient).iter() {
+                assert_eq!(d.rotate_cw(orient).rotate_ccw(orient), *d);
+                let mut r = *d;
+                for _ in 0..6 {
+                    r = r.rotate_cw(orient);
+                }
+                assert_eq!(r, *d);
+            }
+        }
+    }
+
+    #[test]
+    fn test_opposite() {
+        assert_eq!(Direction::N.opposite(), Direction::S);
+        assert_eq!(Direction::NE.opposite(), Direction::SW);
+        assert_eq!(Diagonal::E.opposite(), Diagonal::W);
+        for orient in [Orientation::Flat, Orientation::Pointy].iter().cloned() {
+            for (i, d) in Direction::all(orient).iter().enumerate() {
+                assert_eq!(d.opposite(), Direction::from_index(i + 3, orient));
+            }
+            for (i, d) in Diagonal::all(orient).iter().enumerate() {
+                assert_eq!(d.opposite(), Diagonal::from_index(i + 3, orient));
+            }
+        }
+    }
+
+    #[test]
+    fn test_validity() {
+        assert!(Direction::N.is_valid(Orientation::Flat));
+        assert!(!Direction::N.is_valid(Orientation::Pointy));
+        assert!(!Direction::E.is_valid(Orientation::Flat));
+        assert!(Diagonal::E.is_valid(Orientation::Flat));
+        assert!(!Diagonal::E.is_valid(Orientation::Pointy));
+    }
+
+    #[test]
+    fn test_between() {
+        let a = CubeCoords::new((0, 0, 0), Orientation::Flat);
+        assert_eq!(Direction::between(&a, &CubeCoords::new((0, 1, -1), Orientation::Flat)), Some(Direction::N));
+        assert_eq!(Direction::between(&a, &CubeCoords::new((2, -1, -1), Orientation::Flat)), None);
+        assert_eq!(Diagonal::between(&a, &CubeCoords::new((2, -1, -1), Orientation::Flat)), Some(Diagonal::E));
+
+        let b = OffsetOddCoords::new((2, 2), Orientation::Pointy);
+        for d in Direction::all(Orientation::Pointy).iter() {
+            let n = b.towards(*d).unwrap();
+            assert_eq!(b.direction_to(&n), Some(*d));
+        }
+        assert!(b.towards(Direction::N).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_vector_overflow() {
+        let _ = (Direction::N * 70_000) * 70_000;
+    }
+}
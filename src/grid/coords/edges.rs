@@ -0,0 +1,270 @@
+use super::{CoordType, CubeCoords, PixelCoord};
+use super::num::{CoordInt, CoordFloat};
+use super::alg::{get_neighbour_cube, cube_direction_index, sub_tuple_3};
+use super::direction::Direction;
+use super::utils::{Orientation, Layout};
+
+/*
+    Coordinates for the edges and corners (vertices) of hexes
+
+    Each edge is shared by two hexes and each vertex by three, so they are stored in a canonical
+    form relative to just one of those hexes: an edge as a hex and one of the neighbour
+    directions 0 to 2, and a vertex as a hex and one of the two corners between neighbour
+    directions 0 and 1 (corner 0) or 1 and 2 (corner 1). Any way of naming the same edge or
+    vertex gives equal coordinates and keys.
+
+    Edge and vertex indices around a hex follow the neighbour indices of `CoordType::neighbour`,
+    edge `i` faces neighbour `i` and vertex `i` sits between neighbours `i` and `i + 1`.
+*/
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EdgeKey<I = i32>(I, I, I, u8);
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VertexKey<I = i32>(I, I, I, u8);
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EdgeCoord<I = i32> { hex: (I, I, I), side: u8, orientation: Orientation }
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VertexCoord<I = i32> { hex: (I, I, I), corner: u8, orientation: Orientation }
+
+// Index into `HexShape::corners` of the corner between neighbours `i` and `i + 1`
+fn corner_index(orientation: Orientation, i: usize) -> usize {
+    match orientation {
+        Orientation::Flat => (6 - i % 6) % 6,
+        Orientation::Pointy => (i + 2) % 6
+    }
+}
+
+// Edges
+
+impl<I: CoordInt> EdgeCoord<I> {
+    // The edge of `hex` facing its neighbour in direction `direction` (as in `CoordType::neighbour`)
+    pub fn new<C: CoordType<Int = I>>(hex: &C, direction: usize) -> Self {
+        let direction = direction % 6;
+        let (cube, side) = match direction {
+            0..=2 => (hex.to_cube(), direction),
+            _ => (get_neighbour_cube(hex.to_cube(), direction), direction - 3)
+        };
+        Self { hex: cube, side: side as u8, orientation: hex.orientation() }
+    }
+
+    pub fn towards<C: CoordType<Int = I>>(hex: &C, direction: Direction) -> Option<Self> {
+        direction.index(hex.orientation()).map(|i| Self::new(hex, i))
+    }
+
+    // The edge shared by two hexes, None if they aren't neighbours
+    pub fn between<C: CoordType<Int = I>>(a: &C, b: &C) -> Option<Self> {
+        cube_direction_index(sub_tuple_3(b.to_cube(), a.to_cube())).map(|i| Self::new(a, i))
+    }
+
+    pub fn get_key(&self) -> EdgeKey<I> {
+        EdgeKey(self.hex.0, self.hex.1, self.hex.2, self.side)
+    }
+
+    pub fn from_key(EdgeKey(x, y, z, side): EdgeKey<I>, orientation: Orientation) -> Self {
+        Self::new(&CubeCoords::new((x, y, z), orientation), side as usize)
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    // The two hexes either side of the edge
+    pub fn hexes<C: CoordType<Int = I>>(&self) -> [C; 2] {
+        let side = self.side as usize;
+        [
+            C::from_cube(self.hex, self.orientation),
+            C::from_cube(get_neighbour_cube(self.hex, side), self.orientation)
+        ]
+    }
+
+    // The vertices at either end of the edge
+    pub fn vertices(&self) -> [VertexCoord<I>; 2] {
+        let (hex, side) = (CubeCoords::new(self.hex, self.orientation), self.side as usize);
+        [VertexCoord::new(&hex, side + 5), VertexCoord::new(&hex, side)]
+    }
+
+    pub fn endpoints_to_pixel<F: CoordFloat, L: Into<Layout<F>>>(&self, layout: L) -> [(F, F); 2] {
+        let ([a, b], layout) = (self.vertices(), layout.into());
+        [a.to_pixel(layout).get(), b.to_pixel(layout).get()]
+    }
+
+    // The midpoint of the edge
+    pub fn to_pixel<F: CoordFloat, L: Into<Layout<F>>>(&self, layout: L) -> PixelCoord<F> {
+        let layout = layout.into();
+        let [(ax, ay), (bx, by)] = self.endpoints_to_pixel(layout);
+        let two = F::from_f64(2.0);
+        PixelCoord::new(((ax + bx) / two, (ay + by) / two), layout)
+    }
+}
+
+// Vertices
+
+impl<I: CoordInt> VertexCoord<I> {
+    // The vertex of `hex` between its neighbours in directions `corner` and `corner + 1`
+    pub fn new<C: CoordType<Int = I>>(hex: &C, corner: usize) -> Self {
+        let corner = corner % 6;
+        let cube = hex.to_cube();
+        let touching = [cube, get_neighbour_cube(cube, corner), get_neighbour_cube(cube, (corner + 1) % 6)];
+        // Exactly one of the three hexes sees the other two as neighbours `t` and `t + 1`
+        let t = corner % 2;
+        let base = *touching.iter().find(|h| {
+            touching.contains(&get_neighbour_cube(**h, t)) && touching.contains(&get_neighbour_cube(**h, t + 1))
+        }).unwrap();
+        Self { hex: base, corner: t as u8, orientation: hex.orientation() }
+    }
+
+    pub fn get_key(&self) -> VertexKey<I> {
+        VertexKey(self.hex.0, self.hex.1, self.hex.2, self.corner)
+    }
+
+    pub fn from_key(VertexKey(x, y, z, corner): VertexKey<I>, orientation: Orientation) -> Self {
+        Self::new(&CubeCoords::new((x, y, z), orientation), corner as usize)
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    // The three hexes meeting at the vertex
+    pub fn hexes<C: CoordType<Int = I>>(&self) -> [C; 3] {
+        let corner = self.corner as usize;
+        [
+            C::from_cube(self.hex, self.orientation),
+            C::from_cube(get_neighbour_cube(self.hex, corner), self.orientation),
+            C::from_cube(get_neighbour_cube(self.hex, corner + 1), self.orientation)
+        ]
+    }
+
+    // The three edges meeting at the vertex
+    pub fn edges(&self) -> [EdgeCoord<I>; 3] {
+        let corner = self.corner as usize;
+        let [hex, first, _]: [CubeCoords<I>; 3] = self.hexes();
+        [
+            EdgeCoord::new(&hex, corner),
+            EdgeCoord::new(&hex, corner + 1),
+            EdgeCoord::new(&first, corner + 2)
+        ]
+    }
+
+    pub fn to_pixel<F: CoordFloat, L: Into<Layout<F>>>(&self, layout: L) -> PixelCoord<F> {
+        let hex = CubeCoords::new(self.hex, self.orientation);
+        let pixel = hex.to_pixel(layout);
+        PixelCoord::new(pixel.corners()[corner_index(self.orientation, self.corner as usize)], pixel.layout())
+    }
+}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use super::super::{AxialCoords, OffsetOddCoords};
+    use super::super::utils::HexShape;
+
+    const ORIENTATIONS: [Orientation; 2] = [Orientation::Flat, Orientation::Pointy];
+
+    fn close((ax, ay): (f32, f32), (bx, by): (f32, f32)) -> bool {
+        (ax - bx).abs() < 1e-4 && (ay - by).abs() < 1e-4
+    }
+
+    fn centre<C: CoordType>(hexes: &[C], layout: Layout) -> (f32, f32) {
+        let n = hexes.len() as f32;
+        let (x, y) = hexes.iter().fold((0.0, 0.0), |(x, y), h| {
+            let (hx, hy) = h.to_pixel(layout).get();
+            (x + hx, y + hy)
+        });
+        (x / n, y / n)
+    }
+
+    #[test]
+    fn test_edge_canonical() {
+        for orient in ORIENTATIONS.iter() {
+            let hex = AxialCoords::new((2, -1), *orient);
+            let edges = hex.edges();
+            assert_eq!(edges.iter().collect::<HashSet<_>>().len(), 6);
+            for (i, edge) in edges.iter().enumerate() {
+                // The neighbour names the same edge from the other side
+                let neighbour = hex.neighbour(i);
+                assert_eq!(EdgeCoord::new(&neighbour, i + 3), *edge);
+                assert_eq!(EdgeCoord::between(&neighbour, &hex), Some(*edge));
+                assert_eq!(EdgeCoord::from_key(edge.get_key(), *orient), *edge);
+
+                let touching: [AxialCoords; 2] = edge.hexes();
+                assert!(touching.contains(&hex) && touching.contains(&neighbour));
+            }
+            assert_eq!(EdgeCoord::between(&hex, &AxialCoords::new((4, -1), *orient)), None);
+        }
+        let flat = CubeCoords::new((0, 0, 0), Orientation::Flat);
+        assert_eq!(EdgeCoord::towards(&flat, Direction::N), Some(EdgeCoord::new(&flat, 2)));
+        assert_eq!(EdgeCoord::towards(&flat, Direction::E), None);
+    }
+
+    #[test]
+    fn test_vertex_canonical() {
+        for orient in ORIENTATIONS.iter() {
+            let hex = CubeCoords::new((1, 2, -3), *orient);
+            let vertices = hex.vertices();
+            assert_eq!(vertices.iter().collect::<HashSet<_>>().len(), 6);
+            for (i, vertex) in vertices.iter().enumerate() {
+                let touching: [CubeCoords; 3] = vertex.hexes();
+                assert!(touching.contains(&hex));
+                assert!(touching.contains(&hex.neighbour(i)));
+                assert!(touching.contains(&hex.neighbour((i + 1) % 6)));
+                // Every hex meeting at the vertex has it as one of its own
+                for other in touching.iter() {
+                    assert!(other.vertices().contains(vertex));
+                }
+                assert_eq!(VertexCoord::from_key(vertex.get_key(), *orient), *vertex);
+            }
+        }
+    }
+
+    #[test]
+    fn test_adjacency() {
+        let hex = OffsetOddCoords::new((3, 4), Orientation::Pointy);
+        for edge in hex.edges().iter() {
+            for vertex in edge.vertices().iter() {
+                assert!(vertex.edges().contains(edge));
+                assert!(hex.vertices().contains(vertex));
+            }
+        }
+        for vertex in hex.vertices().iter() {
+            let edges = vertex.edges();
+            assert_eq!(edges.iter().collect::<HashSet<_>>().len(), 3);
+            // Two of the edges at each vertex of a hex belong to the hex
+            assert_eq!(edges.iter().filter(|e| hex.edges().contains(e)).count(), 2);
+        }
+    }
+
+    #[test]
+    fn test_pixels() {
+        for orient in ORIENTATIONS.iter() {
+            let layout = Layout::from(HexShape::new(10.0, *orient));
+            let hex = CubeCoords::new((2, -3, 1), *orient);
+            let corners = hex.to_pixel(layout).corners();
+            for vertex in hex.vertices().iter() {
+                let hexes: [CubeCoords; 3] = vertex.hexes();
+                let position = vertex.to_pixel(layout).get();
+                assert!(close(position, centre(&hexes, layout)));
+                assert!(corners.iter().any(|c| close(*c, position)));
+            }
+            for edge in hex.edges().iter() {
+                let hexes: [CubeCoords; 2] = edge.hexes();
+                assert!(close(edge.to_pixel(layout).get(), centre(&hexes, layout)));
+                for end in edge.endpoints_to_pixel(layout).iter() {
+                    assert!(corners.iter().any(|c| close(*c, *end)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use super::{OffsetOddCoords, OffsetEvenCoords, DoubledCoords, CubeCoords, AxialCoords};
+use super::num::CoordInt;
+use super::utils::Orientation;
+
+/*
+    Text representation of coordinates, e.g. `cube(1,-2,1)/flat` or `offset_odd(4,7)/pointy`
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseCoordError {
+    InvalidFormat,
+    InvalidOrientation,
+    InvalidValue(ParseIntError),
+    InvalidCube
+}
+
+impl fmt::Display for ParseCoordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCoordError::InvalidFormat => write!(f, "expected a coordinate of the form name(a,b)/orientation"),
+            ParseCoordError::InvalidOrientation => write!(f, "expected an orientation of flat or pointy"),
+            ParseCoordError::InvalidValue(e) => write!(f, "invalid coordinate value: {}", e),
+            ParseCoordError::InvalidCube => write!(f, "cube coordinate values must sum to zero")
+        }
+    }
+}
+
+impl Error for ParseCoordError {}
+
+// Orientation
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Orientation::Flat => write!(f, "flat"),
+            Orientation::Pointy => write!(f, "pointy")
+        }
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = ParseCoordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "flat" => Ok(Orientation::Flat),
+            "pointy" => Ok(Orientation::Pointy),
+            _ => Err(ParseCoordError::InvalidOrientation)
+        }
+    }
+}
+
+// Splits `name(a,b,...)/orientation` into its values, checking the name and value count
+fn parse_coord<I: CoordInt>(s: &str, name: &str, count: usize) -> Result<(Vec<I>, Orientation), ParseCoordError> {
+    let mut parts = s.trim().splitn(2, '/');
+    let (coord, orientation) = match (parts.next(), parts.next()) {
+        (Some(coord), Some(orientation)) => (coord.trim(), orientation.parse()?),
+        _ => return Err(ParseCoordError::InvalidFormat)
+    };
+    if !coord.starts_with(name) || !coord.ends_with(')') {
+        return Err(ParseCoordError::InvalidFormat)
+    }
+    let inner = coord[name.len()..coord.len() - 1].trim_start();
+    if !inner.starts_with('(') {
+        return Err(ParseCoordError::InvalidFormat)
+    }
+    let values = inner[1..].split(',')
+        .map(|v| v.trim().parse::<I>().map_err(ParseCoordError::InvalidValue))
+        .collect::<Result<Vec<I>, ParseCoordError>>()?;
+    if values.len() != count {
+        return Err(ParseCoordError::InvalidFormat)
+    }
+    Ok((values, orientation))
+}
+
+macro_rules! impl_coord_format {
+    ($type:ident, $name:expr, ($($value_name:ident),*), $count:expr, $is_valid:expr) => {
+        impl<I: CoordInt> fmt::Display for $type<I> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let values: Vec<String> = vec![$(self.$value_name.to_string()),*];
+                write!(f, "{}({})/{}", $name, values.join(","), self.orientation)
+            }
+        }
+
+        impl<I: CoordInt> FromStr for $type<I> {
+            type Err = ParseCoordError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let (values, orientation) = parse_coord(s, $name, $count)?;
+                if !$is_valid(&values) {
+                    return Err(ParseCoordError::InvalidCube)
+                }
+                let mut values = values.into_iter();
+                Ok($type { $($value_name: values.next().unwrap()),*, orientation })
+            }
+        }
+    }
+}
+
+fn any_values<I: CoordInt>(_: &[I]) -> bool {
+    true
+}
+
+// Summed in the widest type so that values near the limits of their own type can't overflow
+pub(super) fn cube_values<I: CoordInt>(values: &[I]) -> bool {
+    values.iter().map(|v| v.to_i128()).sum::<i128>() == 0
+}
+
+impl_coord_format!(OffsetOddCoords, "offset_odd", (x, y), 2, any_values);
+impl_coord_format!(OffsetEvenCoords, "offset_even", (x, y), 2, any_values);
+impl_coord_format!(DoubledCoords, "doubled", (x, y), 2, any_values);
+impl_coord_format!(CubeCoords, "cube", (x, y, z), 3, cube_values);
+impl_coord_format!(AxialCoords, "axial", (q, r), 2, any_values);
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::CoordType;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(CubeCoords::new((1, -2, 1), Orientation::Flat).to_string(), "cube(1,-2,1)/flat");
+        assert_eq!(AxialCoords::new((3, -4), Orientation::Pointy).to_string(), "axial(3,-4)/pointy");
+        assert_eq!(OffsetOddCoords::new((0, 7), Orientation::Flat).to_string(), "offset_odd(0,7)/flat");
+        assert_eq!(OffsetEvenCoords::new((2, 2), Orientation::Pointy).to_string(), "offset_even(2,2)/pointy");
+        assert_eq!(DoubledCoords::new((5, -1), Orientation::Flat).to_string(), "doubled(5,-1)/flat");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cube = CubeCoords::new((1, -2, 1), Orientation::Flat);
+        assert_eq!(cube.to_string().parse::<CubeCoords>(), Ok(cube));
+        let offset = OffsetEvenCoords::new((-3, 12), Orientation::Pointy);
+        assert_eq!(offset.to_string().parse::<OffsetEvenCoords>(), Ok(offset));
+        assert_eq!(" axial( 1, 2 ) / pointy ".parse::<AxialCoords>(), Ok(AxialCoords::new((1, 2), Orientation::Pointy)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("cube(1,-2,1)".parse::<CubeCoords>(), Err(ParseCoordError::InvalidFormat));
+        assert_eq!("axial(1,-2,1)/flat".parse::<CubeCoords>(), Err(ParseCoordError::InvalidFormat));
+        assert_eq!("cube(1,-2)/flat".parse::<CubeCoords>(), Err(ParseCoordError::InvalidFormat));
+        assert_eq!("cube(1,-2,1)/round".parse::<CubeCoords>(), Err(ParseCoordError::InvalidOrientation));
+        assert_eq!("cube(1,2,1)/flat".parse::<CubeCoords>(), Err(ParseCoordError::InvalidCube));
+        assert_eq!("cube(2147483647,2147483647,2)/flat".parse::<CubeCoords>(), Err(ParseCoordError::InvalidCube));
+        assert!("cube(2147483647,1,-2147483648)/flat".parse::<CubeCoords>().is_ok());
+        match "axial(1,x)/flat".parse::<AxialCoords>() {
+            Err(ParseCoordError::InvalidValue(_)) => (),
+            other => panic!("unexpected result {:?}", other)
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use super::{convert, alg, CoordType, PixelCoord};
+use super::utils::{Orientation, Layout};
+use super::num::{CoordInt, CoordFloat};
+
+/*
+    Fractional coordinates, for positions anywhere in hex space rather than just hex centers
+
+    Useful for smoothly moving things between hexes, or finding where in a hex a pixel lies.
+    Rounding gives the hex containing the position, in any coordinate system.
+
+    Positions can be `f32` (the default) or `f64`, like pixel positions.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FractionalCube<F = f32> { x: F, y: F, z: F, orientation: Orientation }
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FractionalAxial<F = f32> { q: F, r: F, orientation: Orientation }
+
+fn cube_identity<F>(cube: (F, F, F)) -> (F, F, F) {
+    cube
+}
+
+fn fractional_axial_to_cube<F: CoordFloat>((q, r): (F, F)) -> (F, F, F) {
+    (q, -q - r, r)
+}
+
+fn fractional_cube_to_axial<F>((x, _y, z): (F, F, F)) -> (F, F) {
+    (x, z)
+}
+
+macro_rules! impl_fractional_type {
+    ($T:ident, $tuple_rep:ty, ($($value_name:ident),*), $to_cube_fn:ident, $from_cube_fn:ident) => {
+        impl<F: CoordFloat> $T<F> {
+            pub fn new(($($value_name),*): $tuple_rep, orientation: Orientation) -> Self {
+                Self { $($value_name),*, orientation }
+            }
+
+            pub fn get(&self) -> $tuple_rep {
+                ($(self.$value_name),*)
+            }
+
+            pub fn orientation(&self) -> Orientation {
+                self.orientation
+            }
+
+            pub fn to_cube(&self) -> (F, F, F) {
+                $to_cube_fn(self.get())
+            }
+
+            pub fn from_cube(cube: (F, F, F), orientation: Orientation) -> Self {
+                Self::new($from_cube_fn(cube), orientation)
+            }
+
+            // The center of a hex
+            pub fn from_coord<C: CoordType>(coord: &C) -> Self {
+                let (x, y, z) = coord.to_cube();
+                Self::from_cube((x.to_float(), y.to_float(), z.to_float()), coord.orientation())
+            }
+
+            // The hex containing this position
+            pub fn round<C: CoordType>(&self) -> C {
+                C::from_cube(convert::cube_round(self.to_cube()), self.orientation)
+            }
+
+            // Where this position is relative to the center of the hex containing it, rounding into
+            // the integer type of `C` (as `round` does)
+            pub fn offset_from_center<C: CoordType>(&self) -> Self {
+                let (x, y, z) = self.to_cube();
+                let (cx, cy, cz): (C::Int, C::Int, C::Int) = convert::cube_round((x, y, z));
+                Self::from_cube((x - cx.to_float(), y - cy.to_float(), z - cz.to_float()), self.orientation)
+            }
+
+            // Linear interpolation, `t` of 0 gives `self` and 1 gives `other`
+            pub fn lerp(&self, other: &Self, t: F) -> Self {
+                Self::from_cube(alg::lerp_cube(self.to_cube(), other.to_cube(), t), self.orientation)
+            }
+
+            pub fn from_pixel<L: Into<Layout<F>>>(p: (F, F), layout: L) -> Self {
+                let layout = layout.into();
+                Self::from_cube(convert::pixel_to_fractional_cube(layout.from_screen(p), layout.unit_shape()), layout.orientation)
+            }
+
+            pub fn to_pixel<L: Into<Layout<F>>>(&self, layout: L) -> PixelCoord<F> {
+                let layout = layout.into();
+                layout.assert_orientation(self.orientation);
+                PixelCoord::new(layout.to_screen(convert::fractional_cube_to_pixel(self.to_cube(), layout.unit_shape())), layout)
+            }
+        }
+    }
+}
+
+impl_fractional_type!(FractionalCube, (F, F, F), (x, y, z), cube_identity, cube_identity);
+impl_fractional_type!(FractionalAxial, (F, F), (q, r), fractional_axial_to_cube, fractional_cube_to_axial);
+
+impl<'a, F: CoordFloat> From<&'a FractionalAxial<F>> for FractionalCube<F> {
+    fn from(axial: &'a FractionalAxial<F>) -> Self {
+        Self::from_cube(axial.to_cube(), axial.orientation)
+    }
+}
+
+impl<'a, F: CoordFloat> From<&'a FractionalCube<F>> for FractionalAxial<F> {
+    fn from(cube: &'a FractionalCube<F>) -> Self {
+        Self::from_cube(cube.to_cube(), cube.orientation)
+    }
+}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{CubeCoords, AxialCoords, OffsetOddCoords, DoubledCoords};
+    use super::super::utils::HexShape;
+
+    fn close((ax, ay): (f32, f32), (bx, by): (f32, f32)) -> bool {
+        (ax - bx).abs() < 1e-4 && (ay - by).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_round_trip_hex_centers() {
+        for orient in [Orientation::Flat, Orientation::Pointy].iter() {
+            let hex = OffsetOddCoords::new((3, -2), *orient);
+            let cube = FractionalCube::from_coord(&hex);
+            let axial = FractionalAxial::from_coord(&hex);
+            assert_eq!(cube.round::<OffsetOddCoords>(), hex);
+            assert_eq!(axial.round::<DoubledCoords>(), DoubledCoords::from(&hex));
+            assert_eq!(FractionalCube::from(&axial), cube);
+            assert_eq!(FractionalAxial::from(&cube), axial);
+            let shape = HexShape::new(10.0, *orient);
+            assert!(close(cube.to_pixel(shape).get(), hex.to_pixel(shape).get()));
+            assert_eq!(cube.offset_from_center::<OffsetOddCoords>().get(), (0.0, 0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_pixels() {
+        for orient in [Orientation::Flat, Orientation::Pointy].iter() {
+            let shape = HexShape::new(10.0, *orient);
+            let hex = CubeCoords::new((1, -3, 2), *orient);
+            let (x, y) = hex.to_pixel(shape).get();
+            // A click a little off center stays in the same hex and keeps its position in it
+            let click = (x + 3.0, y - 2.0);
+            let position = FractionalCube::from_pixel(click, shape);
+            assert_eq!(position.round::<CubeCoords>(), hex);
+            assert_eq!(position.round::<CubeCoords>(), CubeCoords::from_pixel(click, shape));
+            assert!(close(position.to_pixel(shape).get(), click));
+            let offset = FractionalAxial::from(&position.offset_from_center::<CubeCoords>());
+            assert!(close(offset.to_pixel(shape).get(), (3.0, -2.0)));
+
+            let layout = Layout { flip_y: true, ..Layout::new(*orient, (20.0, 12.0), (50.0, 60.0)) };
+            let screen = (123.0, -45.0);
+            let position = FractionalAxial::from_pixel(screen, layout);
+            assert!(close(position.to_pixel(layout).get(), screen));
+            assert_eq!(position.round::<AxialCoords>(), AxialCoords::from_pixel(screen, layout));
+        }
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = FractionalCube::from_coord(&CubeCoords::new((0, 0, 0), Orientation::Flat));
+        let b = FractionalCube::from_coord(&CubeCoords::new((4, -4, 0), Orientation::Flat));
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert_eq!(a.lerp(&b, 0.5).get(), (2.0, -2.0, 0.0));
+        assert_eq!(a.lerp(&b, 0.3).round::<CubeCoords>().get(), (1, -1, 0));
+
+        // Smooth movement between neighbours crosses into the next hex half way
+        let start = FractionalAxial::from_coord(&AxialCoords::new((0, 0), Orientation::Pointy));
+        let end = FractionalAxial::from_coord(&AxialCoords::new((0, 1), Orientation::Pointy));
+        assert_eq!(start.lerp(&end, 0.45).round::<AxialCoords>().get(), (0, 0));
+        assert_eq!(start.lerp(&end, 0.55).round::<AxialCoords>().get(), (0, 1));
+    }
+
+    #[test]
+    fn test_wide_positions() {
+        // Beyond the range of i32 and the precision of f32
+        let far = 3_000_000_000_001i64;
+        let hex = CubeCoords::new((far, -far - 2, 2), Orientation::Flat);
+        let center = FractionalCube::<f64>::from_coord(&hex);
+        assert_eq!(center.get(), (far as f64, (-far - 2) as f64, 2.0));
+        assert_eq!(center.round::<CubeCoords<i64>>(), hex);
+
+        let neighbour = FractionalCube::from_coord(&hex.neighbour(0));
+        let position = center.lerp(&neighbour, 0.25);
+        let offset = position.offset_from_center::<CubeCoords<i64>>();
+        assert!((offset.get().0 - 0.25).abs() < 1e-9);
+        assert_eq!(position.round::<AxialCoords<i64>>(), AxialCoords::from(&hex));
+
+        let shape = HexShape::new(10.0f64, Orientation::Flat);
+        let pixel = position.to_pixel(shape);
+        let back = FractionalAxial::from_pixel(pixel.get(), shape);
+        assert_eq!(back.round::<CubeCoords<i64>>(), hex);
+    }
+}
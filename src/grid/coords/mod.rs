@@ -1,195 +1,504 @@
-mod convert;
-pub mod alg;
-pub mod utils;
-
-use self::utils::{Orientation, HexShape};
-
-/*
-    Arbretrary 'Key' type for use in dictionary storage
-    Must be unique for every coordinate within a given system
-*/
-
-#[derive(Clone, Copy, Hash, Eq, PartialEq, Default)]
-pub struct CoordKey(i32, i32, i32);
-
-impl From<(i32, i32)> for CoordKey {
-    fn from((x, y): (i32, i32)) -> CoordKey {
-        CoordKey(x, y, 0)
-    }
-}
-
-impl From<(i32, i32, i32)> for CoordKey {
-    fn from((x, y, z): (i32, i32, i32)) -> CoordKey {
-        CoordKey(x, y, z)
-    }
-}
-
-
-/*
-    Pixel position structs/methods for mapping onto a screen
-*/
-
-pub struct PixelCoord { x: f32, y: f32, shape: HexShape }
-
-impl PixelCoord {
-    pub fn new((x, y): (f32, f32), shape: HexShape) -> Self { Self { x, y, shape } }
-    pub fn get(&self) -> (f32, f32) { (self.x, self.y) }
-
-    pub fn corners(&self) -> [(f32, f32); 6] {
-        self.shape.corners(self.get())
-    }
-}
-
-/*
-    Coordinate system definitions
-*/
-
-// Flat systems
-pub struct OffsetOddCoords { x: i32, y: i32, orientation: Orientation }
-pub struct OffsetEvenCoords { x: i32, y: i32, orientation: Orientation }
-pub struct DoubledCoords { x: i32, y: i32, orientation: Orientation }
-pub struct CubeCoords { x: i32, y: i32, z: i32, orientation: Orientation }
-pub struct AxialCoords { q: i32, r: i32, orientation: Orientation }
-
-/*
-    Coordinate type traits
-*/
-
-pub trait CoordType: Sized {
-    type TupleRep;
-
-    fn new(Self::TupleRep, Orientation) -> Self;
-    fn get(&self) -> Self::TupleRep;
-    fn get_key(&self) -> CoordKey;
-
-    fn from_pixel(p: &PixelCoord) -> Self;
-
-    fn to_pixel(&self, radius: f32) -> PixelCoord;
-
-    fn orientation(&self) -> Orientation;
-}
-
-/*
-    Coordinate core method definitions
-*/
-
-macro_rules! impl_coord_type_trait {
-    ($type:ident, $tuple_rep:ty, ($($value_name:ident),*), 
-     $from_pixel_fn:ident, $to_pixel_fn:ident) => {
-        impl CoordType for $type {
-            type TupleRep = $tuple_rep;
-
-            fn new(($($value_name),*): $tuple_rep, orientation: Orientation) -> Self { 
-                Self { $($value_name),*, orientation } 
-            }
-            fn get(&self) -> $tuple_rep { ($(self.$value_name),*) }
-            fn get_key(&self) -> CoordKey { CoordKey::from(($(self.$value_name),*)) }
-
-            fn from_pixel(p: &PixelCoord) -> Self {
-                Self::new(convert::$from_pixel_fn(p.get(), p.shape), p.shape.orient())
-            }
-
-            fn to_pixel(&self, radius: f32) -> PixelCoord {
-                let shape = HexShape::new(radius, self.orientation);
-                PixelCoord::new(convert::$to_pixel_fn(self.get(), shape), shape)
-            }
-
-            fn orientation(&self) -> Orientation {
-                self.orientation
-            }
-        }
-    }
-}
-
-impl_coord_type_trait!(OffsetOddCoords, (i32, i32), (x, y),
-    pixel_to_offset_odd, offset_odd_to_pixel);
-
-impl_coord_type_trait!(OffsetEvenCoords, (i32, i32), (x, y),
-    pixel_to_offset_even, offset_even_to_pixel);
-
-impl_coord_type_trait!(DoubledCoords, (i32, i32), (x, y),
-    pixel_to_doubled, doubled_to_pixel);
-
-impl_coord_type_trait!(CubeCoords, (i32, i32, i32), (x, y, z),
-    pixel_to_cube, cube_to_pixel);
-
-impl_coord_type_trait!(AxialCoords, (i32, i32), (q, r),
-    pixel_to_axial, axial_to_pixel);
-
-/*
-    Define type conversions with from/into syntax
-*/
-
-
-macro_rules! impl_from_traits {
-    ($T:ident, $(($from_type:ident, $f:ident)),*) => {
-        $(
-            impl<'a> From<&'a $from_type> for $T {
-                fn from(input: &'a $from_type) -> Self {
-                    Self::new(convert::$f(input.get(), input.orientation), input.orientation)
-                }
-            }
-        )*
-
-        impl<'a> From<&'a PixelCoord> for $T {
-            fn from(input: &'a PixelCoord) -> Self {
-                Self::from_pixel(input)
-            }
-        }
-
-        // impl<'a> From<&'a $T> for PixelCoord {
-        //     fn from(input: &'a $T) -> PixelCoord {
-        //         $T::to_relative_pixel(input)
-        //     }
-        // }
-    }
-}
-
-impl_from_traits!(OffsetOddCoords, (OffsetEvenCoords, offset_even_to_offset_odd),
-    (DoubledCoords, doubled_to_offset_odd), (CubeCoords, cube_to_offset_odd), (AxialCoords, axial_to_offset_odd));
-
-impl_from_traits!(OffsetEvenCoords, (OffsetOddCoords, offset_odd_to_offset_even),
-    (DoubledCoords, doubled_to_offset_even), (CubeCoords, cube_to_offset_even), (AxialCoords, axial_to_offset_even));
-
-impl_from_traits!(DoubledCoords, (OffsetOddCoords, offset_odd_to_doubled),
-    (OffsetEvenCoords, offset_even_to_doubled), (CubeCoords, cube_to_doubled), (AxialCoords, axial_to_doubled));
-
-impl_from_traits!(CubeCoords, (OffsetOddCoords, offset_odd_to_cube),
-    (OffsetEvenCoords, offset_even_to_cube), (DoubledCoords, doubled_to_cube), (AxialCoords, axial_to_cube_orient));
-
-impl_from_traits!(AxialCoords, (OffsetOddCoords, offset_odd_to_axial),
-    (OffsetEvenCoords, offset_even_to_axial), (DoubledCoords, doubled_to_axial), (CubeCoords, cube_to_axial_orient));
-
-/*
-    TESTING!!!!
-*/
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /*
-        Type Conversion Tests
-    */
-    #[test]
-    fn test_from_conversions() {
-        let input: OffsetOddCoords = OffsetOddCoords::new((5, 8), Orientation::Flat);
-        let output: OffsetEvenCoords = OffsetEvenCoords::from(&input);
-        assert_eq!(input.x, output.x);
-
-        let input: OffsetEvenCoords = OffsetEvenCoords::new((5, 8), Orientation::Flat);
-        let output: OffsetOddCoords = OffsetOddCoords::from(&input);
-        assert_eq!(input.x, output.x);
-    }
-
-    #[test]
-    fn test_into_conversions() {
-        let input: OffsetOddCoords = OffsetOddCoords::new((5, 8), Orientation::Flat);
-        let output: OffsetEvenCoords = (&input).into();
-        assert_eq!(input.x, output.x);
-
-        let input: OffsetEvenCoords = OffsetEvenCoords::new((5, 8), Orientation::Flat);
-        let output: OffsetOddCoords = (&input).into();
-        assert_eq!(input.x, output.x);
-    }
-}
+mod convert;
+pub mod alg;
+pub mod direction;
+pub mod edges;
+pub mod fractional;
+pub mod num;
+mod ops;
+mod format;
+pub mod utils;
+
+use self::utils::{Orientation, Layout};
+use self::direction::{Direction, Diagonal};
+use self::edges::{EdgeCoord, VertexCoord};
+use self::num::{CoordInt, CoordFloat};
+
+pub use self::format::ParseCoordError;
+
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+
+/*
+    Arbretrary 'Key' type for use in dictionary storage
+    Must be unique for every coordinate within a given system
+*/
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CoordKey<I = i32>(I, I, I);
+
+impl<I: CoordInt> From<(I, I)> for CoordKey<I> {
+    fn from((x, y): (I, I)) -> CoordKey<I> {
+        CoordKey(x, y, I::ZERO)
+    }
+}
+
+impl<I: CoordInt> From<(I, I, I)> for CoordKey<I> {
+    fn from((x, y, z): (I, I, I)) -> CoordKey<I> {
+        CoordKey(x, y, z)
+    }
+}
+
+impl<I: CoordInt> From<CoordKey<I>> for (I, I) {
+    fn from(CoordKey(x, y, _): CoordKey<I>) -> (I, I) {
+        (x, y)
+    }
+}
+
+impl<I: CoordInt> From<CoordKey<I>> for (I, I, I) {
+    fn from(CoordKey(x, y, z): CoordKey<I>) -> (I, I, I) {
+        (x, y, z)
+    }
+}
+
+
+/*
+    Pixel position structs/methods for mapping onto a screen
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelCoord<F = f32> { x: F, y: F, layout: Layout<F> }
+
+impl<F: CoordFloat> PixelCoord<F> {
+    pub fn new<L: Into<Layout<F>>>((x, y): (F, F), layout: L) -> Self { Self { x, y, layout: layout.into() } }
+    pub fn get(&self) -> (F, F) { (self.x, self.y) }
+    pub fn layout(&self) -> Layout<F> { self.layout }
+
+    pub fn corners(&self) -> [(F, F); 6] {
+        self.layout.corners(self.get())
+    }
+}
+
+/*
+    Coordinate system definitions
+
+    Values can be any of the integer types supported by `num::CoordInt`, defaulting to `i32`
+*/
+
+// Flat systems
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OffsetOddCoords<I = i32> { x: I, y: I, orientation: Orientation }
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OffsetEvenCoords<I = i32> { x: I, y: I, orientation: Orientation }
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DoubledCoords<I = i32> { x: I, y: I, orientation: Orientation }
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedCube<I>", bound(deserialize = "I: CoordInt + ::serde::Deserialize<'de>")))]
+pub struct CubeCoords<I = i32> { x: I, y: I, z: I, orientation: Orientation }
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AxialCoords<I = i32> { q: I, r: I, orientation: Orientation }
+
+// Cube values as deserialized, which are only accepted if they sum to zero
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct UncheckedCube<I> { x: I, y: I, z: I, orientation: Orientation }
+
+#[cfg(feature = "serde")]
+impl<I: CoordInt> TryFrom<UncheckedCube<I>> for CubeCoords<I> {
+    type Error = ParseCoordError;
+
+    fn try_from(cube: UncheckedCube<I>) -> Result<Self, Self::Error> {
+        if !format::cube_values(&[cube.x, cube.y, cube.z]) {
+            return Err(ParseCoordError::InvalidCube)
+        }
+        Ok(CubeCoords { x: cube.x, y: cube.y, z: cube.z, orientation: cube.orientation })
+    }
+}
+
+/*
+    Coordinate type traits
+
+    Implemented for coordinates with any of the integer types supported by `num::CoordInt`, the
+    grid types and algorithms work in the integer type of the coordinates they're given
+*/
+
+pub trait CoordType: Sized + Copy {
+    type Int: CoordInt;
+    type TupleRep;
+
+    fn new(Self::TupleRep, Orientation) -> Self;
+    fn get(&self) -> Self::TupleRep;
+    fn get_key(&self) -> CoordKey<Self::Int>;
+    fn from_key(key: CoordKey<Self::Int>, orientation: Orientation) -> Self;
+
+    // Pixel conversions take a `Layout`, or a `HexShape` for unscaled hexes with hex (0, 0) at (0, 0),
+    // and panic if its orientation differs from the coordinate's
+    fn from_pixel<F: CoordFloat, L: Into<Layout<F>>>(p: (F, F), layout: L) -> Self;
+
+    fn to_pixel<F: CoordFloat, L: Into<Layout<F>>>(&self, layout: L) -> PixelCoord<F>;
+
+    fn orientation(&self) -> Orientation;
+
+    fn to_cube(&self) -> (Self::Int, Self::Int, Self::Int);
+    fn from_cube(cube: (Self::Int, Self::Int, Self::Int), orientation: Orientation) -> Self;
+
+    fn neighbour(&self, direction: usize) -> Self;
+
+    fn neighbours(&self) -> [Self; 6] {
+        [
+            self.neighbour(0),
+            self.neighbour(1),
+            self.neighbour(2),
+            self.neighbour(3),
+            self.neighbour(4),
+            self.neighbour(5)
+        ]
+    }
+
+    fn edges(&self) -> [EdgeCoord<Self::Int>; 6] {
+        [0, 1, 2, 3, 4, 5].map(|i| EdgeCoord::new(self, i))
+    }
+
+    fn vertices(&self) -> [VertexCoord<Self::Int>; 6] {
+        [0, 1, 2, 3, 4, 5].map(|i| VertexCoord::new(self, i))
+    }
+
+    fn towards(&self, direction: Direction) -> Option<Self> {
+        direction.index(self.orientation()).map(|i| self.neighbour(i))
+    }
+
+    fn diagonal(&self, diagonal: Diagonal) -> Option<Self> {
+        let orientation = self.orientation();
+        diagonal.index(orientation)
+            .map(|i| Self::from_cube(alg::get_diagonal_cube(self.to_cube(), i), orientation))
+    }
+
+    fn direction_to(&self, other: &Self) -> Option<Direction> {
+        Direction::between(self, other)
+    }
+
+    fn rotate(&self, center: &Self, steps: i32) -> Self {
+        Self::from_cube(alg::rotate_cube(self.to_cube(), center.to_cube(), steps), self.orientation())
+    }
+
+    fn reflect_q(&self, center: &Self) -> Self {
+        Self::from_cube(alg::reflect_cube_q(self.to_cube(), center.to_cube()), self.orientation())
+    }
+
+    fn reflect_r(&self, center: &Self) -> Self {
+        Self::from_cube(alg::reflect_cube_r(self.to_cube(), center.to_cube()), self.orientation())
+    }
+
+    fn reflect_s(&self, center: &Self) -> Self {
+        Self::from_cube(alg::reflect_cube_s(self.to_cube(), center.to_cube()), self.orientation())
+    }
+
+    fn distance(&self, other: &Self) -> Self::Int;
+
+    fn line_to(&self, other: &Self) -> Vec<Self> {
+        alg::line(self, other)
+    }
+}
+
+/*
+    Coordinate core method definitions
+*/
+
+macro_rules! impl_coord_type {
+    ($type:ident, $tuple_rep:ty, ($($value_name:ident),*),
+     $from_pixel_fn:ident, $to_pixel_fn:ident, $to_cube_fn:ident, $from_cube_fn:ident,
+     $neighbour_fn:ident, $distance_fn:ident) => {
+        impl<I: CoordInt> CoordType for $type<I> {
+            type Int = I;
+            type TupleRep = $tuple_rep;
+
+            fn new(($($value_name),*): $tuple_rep, orientation: Orientation) -> Self {
+                Self { $($value_name),*, orientation }
+            }
+            fn get(&self) -> $tuple_rep { ($(self.$value_name),*) }
+            fn get_key(&self) -> CoordKey<I> { CoordKey::from(($(self.$value_name),*)) }
+            fn from_key(key: CoordKey<I>, orientation: Orientation) -> Self { Self::new(key.into(), orientation) }
+
+            fn from_pixel<F: CoordFloat, L: Into<Layout<F>>>(p: (F, F), layout: L) -> Self {
+                let layout = layout.into();
+                Self::new(convert::$from_pixel_fn(layout.from_screen(p), layout.unit_shape()), layout.orientation)
+            }
+
+            fn to_pixel<F: CoordFloat, L: Into<Layout<F>>>(&self, layout: L) -> PixelCoord<F> {
+                let layout = layout.into();
+                layout.assert_orientation(self.orientation);
+                PixelCoord::new(layout.to_screen(convert::$to_pixel_fn(self.get(), layout.unit_shape())), layout)
+            }
+
+            fn orientation(&self) -> Orientation {
+                self.orientation
+            }
+
+            fn to_cube(&self) -> (I, I, I) {
+                convert::$to_cube_fn(self.get(), self.orientation)
+            }
+
+            fn from_cube(cube: (I, I, I), orientation: Orientation) -> Self {
+                Self::new(convert::$from_cube_fn(cube, orientation), orientation)
+            }
+
+            fn neighbour(&self, direction: usize) -> Self {
+                Self::new(alg::$neighbour_fn(self.get(), direction, self.orientation), self.orientation)
+            }
+
+            fn distance(&self, other: &Self) -> I {
+                alg::$distance_fn(self.get(), other.get(), self.orientation)
+            }
+        }
+
+        impl<I: CoordInt> $type<I> {
+            // The same coordinate with another integer type, if its values fit
+            pub fn cast<J: CoordInt>(&self) -> Option<$type<J>> {
+                Some($type { $($value_name: J::from_i128(self.$value_name.to_i128())?),*, orientation: self.orientation })
+            }
+        }
+    }
+}
+
+impl_coord_type!(OffsetOddCoords, (I, I), (x, y),
+    pixel_to_offset_odd, offset_odd_to_pixel,
+    offset_odd_to_cube, cube_to_offset_odd,
+    get_neighbour_offset_odd, distance_offset_odd);
+
+impl_coord_type!(OffsetEvenCoords, (I, I), (x, y),
+    pixel_to_offset_even, offset_even_to_pixel,
+    offset_even_to_cube, cube_to_offset_even,
+    get_neighbour_offset_even, distance_offset_even);
+
+impl_coord_type!(DoubledCoords, (I, I), (x, y),
+    pixel_to_doubled, doubled_to_pixel,
+    doubled_to_cube, cube_to_doubled,
+    get_neighbour_doubled, distance_doubled);
+
+impl_coord_type!(CubeCoords, (I, I, I), (x, y, z),
+    pixel_to_cube, cube_to_pixel,
+    identity_ignore, identity_ignore,
+    get_neighbour_cube_orient, distance_cube_orient);
+
+impl_coord_type!(AxialCoords, (I, I), (q, r),
+    pixel_to_axial, axial_to_pixel,
+    axial_to_cube_orient, cube_to_axial_orient,
+    get_neighbour_axial_orient, distance_axial_orient);
+
+/*
+    Define type conversions with from/into syntax
+*/
+
+
+macro_rules! impl_from_traits {
+    ($T:ident, $(($from_type:ident, $f:ident)),*) => {
+        $(
+            impl<'a, I: CoordInt> From<&'a $from_type<I>> for $T<I> {
+                fn from(input: &'a $from_type<I>) -> Self {
+                    Self::new(convert::$f(input.get(), input.orientation), input.orientation)
+                }
+            }
+        )*
+
+        impl<'a, I: CoordInt, F: CoordFloat> From<&'a PixelCoord<F>> for $T<I> {
+            fn from(input: &'a PixelCoord<F>) -> Self {
+                Self::from_pixel(input.get(), input.layout())
+            }
+        }
+
+        // impl<'a> From<&'a $T> for PixelCoord {
+        //     fn from(input: &'a $T) -> PixelCoord {
+        //         $T::to_relative_pixel(input)
+        //     }
+        // }
+    }
+}
+
+impl_from_traits!(OffsetOddCoords, (OffsetEvenCoords, offset_even_to_offset_odd),
+    (DoubledCoords, doubled_to_offset_odd), (CubeCoords, cube_to_offset_odd), (AxialCoords, axial_to_offset_odd));
+
+impl_from_traits!(OffsetEvenCoords, (OffsetOddCoords, offset_odd_to_offset_even),
+    (DoubledCoords, doubled_to_offset_even), (CubeCoords, cube_to_offset_even), (AxialCoords, axial_to_offset_even));
+
+impl_from_traits!(DoubledCoords, (OffsetOddCoords, offset_odd_to_doubled),
+    (OffsetEvenCoords, offset_even_to_doubled), (CubeCoords, cube_to_doubled), (AxialCoords, axial_to_doubled));
+
+impl_from_traits!(CubeCoords, (OffsetOddCoords, offset_odd_to_cube),
+    (OffsetEvenCoords, offset_even_to_cube), (DoubledCoords, doubled_to_cube), (AxialCoords, axial_to_cube_orient));
+
+impl_from_traits!(AxialCoords, (OffsetOddCoords, offset_odd_to_axial),
+    (OffsetEvenCoords, offset_even_to_axial), (DoubledCoords, doubled_to_axial), (CubeCoords, cube_to_axial_orient));
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::utils::HexShape;
+
+    /*
+        Type Conversion Tests
+    */
+    #[test]
+    fn test_from_conversions() {
+        let input: OffsetOddCoords = OffsetOddCoords::new((5, 8), Orientation::Flat);
+        let output: OffsetEvenCoords = OffsetEvenCoords::from(&input);
+        assert_eq!(input.x, output.x);
+
+        let input: OffsetEvenCoords = OffsetEvenCoords::new((5, 8), Orientation::Flat);
+        let output: OffsetOddCoords = OffsetOddCoords::from(&input);
+        assert_eq!(input.x, output.x);
+    }
+
+    #[test]
+    fn test_into_conversions() {
+        let input: OffsetOddCoords = OffsetOddCoords::new((5, 8), Orientation::Flat);
+        let output: OffsetEvenCoords = (&input).into();
+        assert_eq!(input.x, output.x);
+
+        let input: OffsetEvenCoords = OffsetEvenCoords::new((5, 8), Orientation::Flat);
+        let output: OffsetOddCoords = (&input).into();
+        assert_eq!(input.x, output.x);
+    }
+
+    /*
+        Neighbour Tests
+    */
+
+    #[test]
+    fn test_neighbours_across_systems() {
+        let cube = CubeCoords::new((1, -3, 2), Orientation::Pointy);
+        let odd = OffsetOddCoords::from(&cube);
+        let doubled = DoubledCoords::from(&cube);
+        for (i, n) in cube.neighbours().iter().enumerate() {
+            assert_eq!(CubeCoords::from(&odd.neighbour(i)).get(), n.get());
+            assert_eq!(CubeCoords::from(&doubled.neighbour(i)).get(), n.get());
+        }
+    }
+
+    /*
+        Rotation/Reflection Tests
+    */
+
+    #[test]
+    fn test_rotate_across_systems() {
+        let center = CubeCoords::new((1, -1, 0), Orientation::Flat);
+        let input = CubeCoords::new((3, -1, -2), Orientation::Flat);
+        let expected = input.rotate(&center, 2).get();
+
+        let rotated = OffsetEvenCoords::from(&input).rotate(&OffsetEvenCoords::from(&center), 2);
+        assert_eq!(CubeCoords::from(&rotated).get(), expected);
+
+        let rotated = DoubledCoords::from(&input).rotate(&DoubledCoords::from(&center), 2);
+        assert_eq!(CubeCoords::from(&rotated).get(), expected);
+
+        let reflected = AxialCoords::new((2, 1), Orientation::Pointy).reflect_q(&AxialCoords::new((0, 0), Orientation::Pointy));
+        assert_eq!(reflected.get(), (2, -3));
+    }
+
+    /*
+        Distance Tests
+    */
+
+    #[test]
+    fn test_distance_across_systems() {
+        let a = CubeCoords::new((0, 0, 0), Orientation::Flat);
+        let b = CubeCoords::new((2, -3, 1), Orientation::Flat);
+        assert_eq!(a.distance(&b), 3);
+        assert_eq!(OffsetOddCoords::from(&a).distance(&OffsetOddCoords::from(&b)), 3);
+        assert_eq!(OffsetEvenCoords::from(&a).distance(&OffsetEvenCoords::from(&b)), 3);
+        assert_eq!(DoubledCoords::from(&a).distance(&DoubledCoords::from(&b)), 3);
+        assert_eq!(AxialCoords::from(&a).distance(&AxialCoords::from(&b)), 3);
+    }
+
+    /*
+        Layout Tests
+    */
+
+    #[test]
+    fn test_layout_round_trip() {
+        for orient in [Orientation::Flat, Orientation::Pointy].iter() {
+            let mut layout = Layout::new(*orient, (30.0, 15.0), (400.0, 300.0));
+            for flip in [false, true].iter() {
+                layout.flip_y = *flip;
+                for cube in alg::get_range_cube((0, 0, 0), 3).into_iter() {
+                    let hex = OffsetOddCoords::from(&CubeCoords::new(cube, *orient));
+                    let pixel = hex.to_pixel(layout);
+                    let (x, y) = pixel.get();
+                    assert_eq!(OffsetOddCoords::from_pixel((x, y), layout), hex);
+                    assert_eq!(OffsetOddCoords::from(&pixel), hex);
+                    // Anywhere well inside the squashed hex picks the same hex
+                    for (cx, cy) in pixel.corners().iter() {
+                        let inside = (x + (cx - x) * 0.8, y + (cy - y) * 0.8);
+                        assert_eq!(OffsetOddCoords::from_pixel(inside, layout), hex);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_layout_transforms() {
+        let hex = AxialCoords::new((0, 1), Orientation::Pointy);
+        let origin = (100.0, 50.0);
+        assert_eq!(AxialCoords::new((0, 0), Orientation::Pointy).to_pixel(Layout::new(Orientation::Pointy, (10.0, 10.0), origin)).get(), origin);
+
+        let shape = HexShape::new(10.0, Orientation::Pointy);
+        let plain = Layout::from(shape);
+        assert_eq!(hex.to_pixel(plain), hex.to_pixel(shape));
+
+        // Squashing only changes the vertical axis, flipping turns it upside down
+        let (x, y) = hex.to_pixel(plain).get();
+        let squashed = Layout::new(Orientation::Pointy, (10.0, 5.0), (0.0, 0.0));
+        assert_eq!(hex.to_pixel(squashed).get(), (x, y / 2.0));
+        let flipped = Layout { flip_y: true, ..plain };
+        assert_eq!(hex.to_pixel(flipped).get(), (x, -y));
+
+        // Converting from pixels gives coordinates with the layout's orientation
+        assert_eq!(AxialCoords::<i32>::from_pixel((x, y), plain).orientation(), Orientation::Pointy);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_layout_orientation_mismatch() {
+        let _ = AxialCoords::new((0, 1), Orientation::Pointy).to_pixel(HexShape::new(10.0f32, Orientation::Flat));
+    }
+
+    /*
+        Numeric Type Tests
+    */
+
+    #[test]
+    fn test_wide_coordinates() {
+        // Far beyond the range of i32, with pixel positions in f64
+        let far = 3_000_000_000_000i64;
+        let cube = CubeCoords::new((far, -far - 7, 7), Orientation::Pointy);
+        let odd = OffsetOddCoords::from(&cube);
+        assert_eq!(CubeCoords::from(&odd), cube);
+        assert_eq!(CubeCoords::from(&DoubledCoords::from(&odd)), cube);
+        assert_eq!(cube.neighbour(0).get(), (far + 1, -far - 8, 7));
+        assert_eq!(cube.distance(&CubeCoords::new((0, 0, 0), Orientation::Pointy)), far + 7);
+
+        let pixel = odd.to_pixel(HexShape::new(10.0f64, Orientation::Pointy));
+        assert_eq!(OffsetOddCoords::<i64>::from_pixel(pixel.get(), pixel.layout()), odd);
+        assert_eq!(cube.cast::<i32>(), None);
+        assert_eq!(cube.to_string().parse::<CubeCoords<i64>>(), Ok(cube));
+    }
+
+    #[test]
+    fn test_narrow_coordinates() {
+        let axial = AxialCoords::new((-120i16, 45), Orientation::Flat);
+        let wide = axial.cast::<i32>().unwrap();
+        assert_eq!(AxialCoords::from(&OffsetEvenCoords::from(&axial)).get(), (-120, 45));
+        assert_eq!(wide.to_cube(), (-120, 75, 45));
+        assert_eq!(wide.cast::<i16>(), Some(axial));
+        let shape = HexShape::new(25.0f32, Orientation::Flat);
+        assert_eq!(axial.to_pixel(shape), wide.to_pixel(shape));
+        assert_eq!((axial + Direction::NE * 3).get(), (-117, 42));
+        assert_eq!(AxialCoords::<i16>::from_key(axial.get_key(), Orientation::Flat), axial);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_coordinate_overflow() {
+        // Doubled coordinates need twice the range of the others
+        let _ = DoubledCoords::from(&OffsetOddCoords::new((3i8, 100), Orientation::Flat));
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::num::ParseIntError;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use std::str::FromStr;
+
+/*
+    Numeric types usable for coordinate values and pixel positions
+
+    Coordinates can be stored in any of `i8`, `i16`, `i32` (the default) or `i64`, and pixel
+    positions in `f32` (the default) or `f64`.
+
+    Overflow is checked the same way in debug and release builds: any arithmetic on coordinate
+    values that doesn't fit the integer type (e.g. a neighbour of a hex at the edge of the range,
+    or converting to doubled coordinates, which needs twice the range) panics, as does rounding
+    a pixel position that lies outside the range. Use `cast` on a coordinate to move it to another
+    integer type, which returns `None` when the values don't fit.
+*/
+
+pub trait CoordInt: Copy + Debug + Display + Default + Hash + Eq + Ord + FromStr<Err = ParseIntError> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn plus(self, other: Self) -> Self;
+    fn minus(self, other: Self) -> Self;
+    fn times(self, other: Self) -> Self;
+    fn negate(self) -> Self;
+    fn magnitude(self) -> Self;
+
+    // Division by two, rounding towards zero
+    fn half(self) -> Self;
+    fn is_odd(self) -> bool;
+
+    fn to_i128(self) -> i128;
+    fn from_i128(value: i128) -> Option<Self>;
+
+    // Panics if the (already rounded) value is outside the range of the type
+    fn from_float<F: CoordFloat>(value: F) -> Self;
+
+    fn to_float<F: CoordFloat>(self) -> F {
+        F::from_f64(self.to_i128() as f64)
+    }
+
+    // Small constants (e.g. direction offsets) which fit every integer type
+    fn from_i8(value: i8) -> Self {
+        match Self::from_i128(value as i128) {
+            Some(v) => v,
+            None => overflow()
+        }
+    }
+}
+
+pub trait CoordFloat: Copy + Debug + PartialEq + PartialOrd
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self> {
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+
+    fn sqrt(self) -> Self;
+    fn round(self) -> Self;
+    fn abs(self) -> Self;
+}
+
+pub(crate) fn overflow() -> ! {
+    panic!("Hex coordinate overflow")
+}
+
+macro_rules! impl_coord_int {
+    ($($type:ident),*) => {
+        $(
+            impl CoordInt for $type {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn plus(self, other: Self) -> Self {
+                    self.checked_add(other).unwrap_or_else(|| overflow())
+                }
+
+                fn minus(self, other: Self) -> Self {
+                    self.checked_sub(other).unwrap_or_else(|| overflow())
+                }
+
+                fn times(self, other: Self) -> Self {
+                    self.checked_mul(other).unwrap_or_else(|| overflow())
+                }
+
+                fn negate(self) -> Self {
+                    self.checked_neg().unwrap_or_else(|| overflow())
+                }
+
+                fn magnitude(self) -> Self {
+                    self.checked_abs().unwrap_or_else(|| overflow())
+                }
+
+                fn half(self) -> Self {
+                    self / 2
+                }
+
+                fn is_odd(self) -> bool {
+                    self & 1 == 1
+                }
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    if value >= $type::MIN as i128 && value <= $type::MAX as i128 {
+                        Some(value as $type)
+                    } else {
+                        None
+                    }
+                }
+
+                fn from_float<F: CoordFloat>(value: F) -> Self {
+                    // MIN is a power of two so converts exactly, and -MIN is the first value past MAX
+                    let (value, min) = (value.to_f64(), $type::MIN as f64);
+                    if value >= min && value < -min {
+                        value as $type
+                    } else {
+                        overflow()
+                    }
+                }
+            }
+        )*
+    }
+}
+
+impl_coord_int!(i8, i16, i32, i64);
+
+macro_rules! impl_coord_float {
+    ($($type:ident),*) => {
+        $(
+            impl CoordFloat for $type {
+                fn from_f64(value: f64) -> Self {
+                    value as $type
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn sqrt(self) -> Self {
+                    $type::sqrt(self)
+                }
+
+                fn round(self) -> Self {
+                    $type::round(self)
+                }
+
+                fn abs(self) -> Self {
+                    $type::abs(self)
+                }
+            }
+        )*
+    }
+}
+
+impl_coord_float!(f32, f64);
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(5i8.plus(-7), -2);
+        assert_eq!((-7i64).half(), -3);
+        assert!((-3i16).is_odd());
+        assert!(!4i32.is_odd());
+        assert_eq!(i8::from_i128(127), Some(127));
+        assert_eq!(i8::from_i128(128), None);
+        assert_eq!(i16::from_float(-32768.0f64), -32768);
+        assert_eq!(i64::from_float(-9.0e18f32), -9.0e18f32 as i64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overflow() {
+        let _ = 127i8.plus(1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_float_overflow() {
+        let _ = i16::from_float(32768.0f32);
+    }
+}
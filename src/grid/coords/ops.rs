@@ -0,0 +1,195 @@
+use std::ops::{Add, Sub, Neg, Mul, AddAssign, SubAssign};
+
+use super::{CoordType, CubeCoords, AxialCoords};
+use super::alg::sum_tuple_3;
+use super::direction::{Direction, DirectionVector};
+use super::num::{self, CoordInt};
+use super::utils::Orientation;
+
+/*
+    Arithmetic on coordinate types where it maps directly onto vector arithmetic
+
+    Combining two coordinates with different orientations is rejected with a panic,
+    direction offsets are resolved against the orientation of the coordinate they're applied to.
+    Results that don't fit the coordinate's integer type panic.
+*/
+
+fn check_orientation(a: Orientation, b: Orientation) {
+    if a != b {
+        panic!("Cannot combine coordinates with different orientations");
+    }
+}
+
+fn direction_offset<I: CoordInt>(orientation: Orientation, offset: DirectionVector) -> (I, I, I) {
+    let (dx, dy, dz) = match offset.to_cube(orientation) {
+        Some(delta) => delta,
+        None => panic!("Direction {:?} is not valid for this coordinate's orientation", offset.direction)
+    };
+    let convert = |v: i32| I::from_i128(v as i128).unwrap_or_else(|| num::overflow());
+    (convert(dx), convert(dy), convert(dz))
+}
+
+macro_rules! impl_coord_ops {
+    ($type:ident, ($($value_name:ident),*)) => {
+        impl<I: CoordInt> $type<I> {
+            fn apply_direction(&self, offset: DirectionVector) -> Self {
+                let cube = sum_tuple_3(self.to_cube(), direction_offset(self.orientation, offset));
+                Self::from_cube(cube, self.orientation)
+            }
+        }
+
+        impl<I: CoordInt> Add for $type<I> {
+            type Output = $type<I>;
+
+            fn add(self, other: $type<I>) -> $type<I> {
+                check_orientation(self.orientation, other.orientation);
+                $type { $($value_name: self.$value_name.plus(other.$value_name)),*, orientation: self.orientation }
+            }
+        }
+
+        impl<I: CoordInt> Sub for $type<I> {
+            type Output = $type<I>;
+
+            fn sub(self, other: $type<I>) -> $type<I> {
+                check_orientation(self.orientation, other.orientation);
+                $type { $($value_name: self.$value_name.minus(other.$value_name)),*, orientation: self.orientation }
+            }
+        }
+
+        impl<I: CoordInt> Neg for $type<I> {
+            type Output = $type<I>;
+
+            fn neg(self) -> $type<I> {
+                $type { $($value_name: self.$value_name.negate()),*, orientation: self.orientation }
+            }
+        }
+
+        impl<I: CoordInt> Mul<I> for $type<I> {
+            type Output = $type<I>;
+
+            fn mul(self, factor: I) -> $type<I> {
+                $type { $($value_name: self.$value_name.times(factor)),*, orientation: self.orientation }
+            }
+        }
+
+        impl<I: CoordInt> AddAssign for $type<I> {
+            fn add_assign(&mut self, other: $type<I>) {
+                *self = *self + other;
+            }
+        }
+
+        impl<I: CoordInt> SubAssign for $type<I> {
+            fn sub_assign(&mut self, other: $type<I>) {
+                *self = *self - other;
+            }
+        }
+
+        impl<I: CoordInt> Add<DirectionVector> for $type<I> {
+            type Output = $type<I>;
+
+            fn add(self, offset: DirectionVector) -> $type<I> {
+                self.apply_direction(offset)
+            }
+        }
+
+        impl<I: CoordInt> Sub<DirectionVector> for $type<I> {
+            type Output = $type<I>;
+
+            fn sub(self, offset: DirectionVector) -> $type<I> {
+                self.apply_direction(-offset)
+            }
+        }
+
+        impl<I: CoordInt> AddAssign<DirectionVector> for $type<I> {
+            fn add_assign(&mut self, offset: DirectionVector) {
+                *self = self.apply_direction(offset);
+            }
+        }
+
+        impl<I: CoordInt> SubAssign<DirectionVector> for $type<I> {
+            fn sub_assign(&mut self, offset: DirectionVector) {
+                *self = self.apply_direction(-offset);
+            }
+        }
+
+        impl<I: CoordInt> Add<Direction> for $type<I> {
+            type Output = $type<I>;
+
+            fn add(self, direction: Direction) -> $type<I> {
+                self.apply_direction(DirectionVector::new(direction, 1))
+            }
+        }
+
+        impl<I: CoordInt> Sub<Direction> for $type<I> {
+            type Output = $type<I>;
+
+            fn sub(self, direction: Direction) -> $type<I> {
+                self.apply_direction(DirectionVector::new(direction, -1))
+            }
+        }
+    }
+}
+
+impl_coord_ops!(CubeCoords, (x, y, z));
+impl_coord_ops!(AxialCoords, (q, r));
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::utils::Orientation;
+
+    #[test]
+    fn test_cube_arithmetic() {
+        let a = CubeCoords::new((1, -3, 2), Orientation::Flat);
+        let b = CubeCoords::new((2, -1, -1), Orientation::Flat);
+        assert_eq!((a + b).get(), (3, -4, 1));
+        assert_eq!((a - b).get(), (-1, -2, 3));
+        assert_eq!((-a).get(), (-1, 3, -2));
+        assert_eq!((b * 3).get(), (6, -3, -3));
+
+        let mut c = a;
+        c += b;
+        assert_eq!(c.get(), (3, -4, 1));
+        c -= b;
+        assert_eq!(c.get(), a.get());
+    }
+
+    #[test]
+    fn test_axial_arithmetic() {
+        let a = AxialCoords::new((1, 2), Orientation::Pointy);
+        let b = AxialCoords::new((-3, 1), Orientation::Pointy);
+        assert_eq!((a + b).get(), (-2, 3));
+        assert_eq!((a - b).get(), (4, 1));
+        assert_eq!((-b * 2).get(), (6, -2));
+    }
+
+    #[test]
+    fn test_direction_offsets() {
+        let a = CubeCoords::new((0, 0, 0), Orientation::Flat);
+        assert_eq!((a + Direction::N * 3).get(), (0, 3, -3));
+        assert_eq!((a + Direction::N).get(), (0, 1, -1));
+        assert_eq!((a - Direction::N * 2).get(), (a + Direction::S * 2).get());
+
+        let mut b = AxialCoords::new((0, 0), Orientation::Pointy);
+        b += Direction::E * 2;
+        assert_eq!(b.get(), (2, 0));
+        b -= Direction::SE * 1;
+        assert_eq!(b.get(), (2, -1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mixed_orientations() {
+        let _ = CubeCoords::new((0, 0, 0), Orientation::Flat) + CubeCoords::new((1, -1, 0), Orientation::Pointy);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_direction() {
+        let _ = CubeCoords::new((0, 0, 0), Orientation::Pointy) + Direction::N * 2;
+    }
+}
@@ -1,83 +1,143 @@
-use std::ops::{Add, Mul};
-
-// Tuple Helpers
-
-pub fn offset_2d_tuple<T: Copy + Add<Output=T>>((x, y): (T, T), (off_x, off_y): (T, T)) -> (T, T) {
-    (x + off_x, y + off_y)
-}
-
-pub fn scale_2d_tuple<T: Copy + Mul<Output=T>>((x, y): (T, T), factor: T) -> (T, T) {
-    (x * factor, y * factor)
-}
-
-pub fn offset_3d_tuple<T: Copy + Add<Output=T>>((x, y, z): (T, T, T), (off_x, off_y, off_z): (T, T, T)) -> (T, T, T) {
-    (x + off_x, y + off_y, z + off_z)
-}
-
-pub fn scale_3d_tuple<T: Copy + Mul<Output=T>>((x, y, z): (T, T, T), factor: T) -> (T, T, T) {
-    (x * factor, y * factor, z * factor)
-}
-
-#[derive(Clone, Copy)]
-pub enum Orientation {
-    Flat,
-    Pointy
-}
-
-pub fn base_corner_coords(orient: Orientation) -> [(f32, f32); 6] {
-    let s = 3f32.sqrt() / 2.0;
-    match orient {
-        Orientation::Flat => [
-            (1.0, 0.0), (0.5, s), (-0.5, s), (-1.0, 0.0), (-0.5, -s), (0.5, -s)
-        ],
-        Orientation::Pointy => [
-            (0.0, 1.0), (s, 0.5), (s, -0.5), (0.0, -1.0), (-s, -0.5), (-s, 0.5)
-        ]
-    }
-}
-
-pub fn corner_coords(offset: (f32, f32), shape: HexShape) -> [(f32, f32); 6] {
-    let (orient, radius) = (shape.orient(), shape.radius());
-    let r = base_corner_coords(orient);
-    [
-        offset_2d_tuple(scale_2d_tuple(r[0], radius), offset),
-        offset_2d_tuple(scale_2d_tuple(r[1], radius), offset),
-        offset_2d_tuple(scale_2d_tuple(r[2], radius), offset),
-        offset_2d_tuple(scale_2d_tuple(r[3], radius), offset),
-        offset_2d_tuple(scale_2d_tuple(r[4], radius), offset),
-        offset_2d_tuple(scale_2d_tuple(r[5], radius), offset)
-    ]
-}
-
-#[derive(Clone, Copy)]
-pub enum HexShape {
-    FlatTop(f32),
-    PointyTop(f32)
-}
-
-impl HexShape {
-    pub fn new(radius: f32, orient: Orientation) -> Self {
-        match orient {
-            Orientation::Flat => HexShape::FlatTop(radius),
-            Orientation::Pointy => HexShape::PointyTop(radius)
-        }
-    }
-    
-    pub fn orient(&self) -> Orientation {
-        match self {
-            HexShape::FlatTop(_) => Orientation::Flat,
-            HexShape::PointyTop(_) => Orientation::Pointy
-        }
-    }
-
-    pub fn radius(&self) -> f32 {
-        match self {
-            HexShape::FlatTop(r) => *r,
-            HexShape::PointyTop(r) => *r
-        }
-    }
-
-    pub fn corners(&self, offset: (f32, f32)) -> [(f32, f32); 6] {
-        corner_coords(offset, *self)
-    }
-}
\ No newline at end of file
+use std::ops::{Add, Mul};
+
+use super::num::CoordFloat;
+
+// Tuple Helpers
+
+pub fn offset_2d_tuple<T: Copy + Add<Output=T>>((x, y): (T, T), (off_x, off_y): (T, T)) -> (T, T) {
+    (x + off_x, y + off_y)
+}
+
+pub fn scale_2d_tuple<T: Copy + Mul<Output=T>>((x, y): (T, T), factor: T) -> (T, T) {
+    (x * factor, y * factor)
+}
+
+pub fn offset_3d_tuple<T: Copy + Add<Output=T>>((x, y, z): (T, T, T), (off_x, off_y, off_z): (T, T, T)) -> (T, T, T) {
+    (x + off_x, y + off_y, z + off_z)
+}
+
+pub fn scale_3d_tuple<T: Copy + Mul<Output=T>>((x, y, z): (T, T, T), factor: T) -> (T, T, T) {
+    (x * factor, y * factor, z * factor)
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Orientation {
+    Flat,
+    Pointy
+}
+
+pub fn base_corner_coords<F: CoordFloat>(orient: Orientation) -> [(F, F); 6] {
+    let f = F::from_f64;
+    let s = f(3.0).sqrt() / f(2.0);
+    match orient {
+        Orientation::Flat => [
+            (f(1.0), f(0.0)), (f(0.5), s), (f(-0.5), s), (f(-1.0), f(0.0)), (f(-0.5), -s), (f(0.5), -s)
+        ],
+        Orientation::Pointy => [
+            (f(0.0), f(1.0)), (s, f(0.5)), (s, f(-0.5)), (f(0.0), f(-1.0)), (-s, f(-0.5)), (-s, f(0.5))
+        ]
+    }
+}
+
+pub fn corner_coords<F: CoordFloat>(offset: (F, F), shape: HexShape<F>) -> [(F, F); 6] {
+    let (orient, radius) = (shape.orient(), shape.radius());
+    let r = base_corner_coords(orient);
+    [
+        offset_2d_tuple(scale_2d_tuple(r[0], radius), offset),
+        offset_2d_tuple(scale_2d_tuple(r[1], radius), offset),
+        offset_2d_tuple(scale_2d_tuple(r[2], radius), offset),
+        offset_2d_tuple(scale_2d_tuple(r[3], radius), offset),
+        offset_2d_tuple(scale_2d_tuple(r[4], radius), offset),
+        offset_2d_tuple(scale_2d_tuple(r[5], radius), offset)
+    ]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HexShape<F = f32> {
+    FlatTop(F),
+    PointyTop(F)
+}
+
+impl<F: CoordFloat> HexShape<F> {
+    pub fn new(radius: F, orient: Orientation) -> Self {
+        match orient {
+            Orientation::Flat => HexShape::FlatTop(radius),
+            Orientation::Pointy => HexShape::PointyTop(radius)
+        }
+    }
+    
+    pub fn orient(&self) -> Orientation {
+        match self {
+            HexShape::FlatTop(_) => Orientation::Flat,
+            HexShape::PointyTop(_) => Orientation::Pointy
+        }
+    }
+
+    pub fn radius(&self) -> F {
+        match self {
+            HexShape::FlatTop(r) => *r,
+            HexShape::PointyTop(r) => *r
+        }
+    }
+
+    pub fn corners(&self, offset: (F, F)) -> [(F, F); 6] {
+        corner_coords(offset, *self)
+    }
+}
+/*
+    Layout of hexes on a screen
+
+    Pixel positions for a hex of radius 1 with hex (0, 0) at (0, 0) are scaled separately along
+    each axis by `size` (so hexes can be squashed, e.g. for an isometric view), optionally flipped
+    vertically (for y-up screens), and then moved by `origin`.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Layout<F = f32> {
+    pub orientation: Orientation,
+    pub size: (F, F),
+    pub origin: (F, F),
+    pub flip_y: bool
+}
+
+impl<F: CoordFloat> Layout<F> {
+    pub fn new(orientation: Orientation, size: (F, F), origin: (F, F)) -> Self {
+        Self { orientation, size, origin, flip_y: false }
+    }
+
+    // From pixel positions for a hex of radius 1 to screen positions
+    pub fn to_screen(&self, (x, y): (F, F)) -> (F, F) {
+        let y = if self.flip_y { -y } else { y };
+        (x * self.size.0 + self.origin.0, y * self.size.1 + self.origin.1)
+    }
+
+    // From screen positions to pixel positions for a hex of radius 1
+    pub fn from_screen(&self, (x, y): (F, F)) -> (F, F) {
+        let (x, y) = ((x - self.origin.0) / self.size.0, (y - self.origin.1) / self.size.1);
+        (x, if self.flip_y { -y } else { y })
+    }
+
+    pub fn unit_shape(&self) -> HexShape<F> {
+        HexShape::new(F::from_f64(1.0), self.orientation)
+    }
+
+    // Screen positions of the corners of a hex centered at a screen position
+    pub fn corners(&self, center: (F, F)) -> [(F, F); 6] {
+        self.unit_shape().corners(self.from_screen(center)).map(|c| self.to_screen(c))
+    }
+
+    // Hexes are only laid out by a layout with the same orientation
+    pub(crate) fn assert_orientation(&self, orientation: Orientation) {
+        assert!(self.orientation == orientation, "Cannot lay out {:?} hexes with a {:?} layout", orientation, self.orientation);
+    }
+}
+
+impl<F: CoordFloat> From<HexShape<F>> for Layout<F> {
+    fn from(shape: HexShape<F>) -> Self {
+        let zero = F::from_f64(0.0);
+        Self::new(shape.orient(), (shape.radius(), shape.radius()), (zero, zero))
+    }
+}
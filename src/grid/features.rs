@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::collections::hash_map;
+use std::iter;
+
+use super::coords::CoordType;
+use super::coords::num::CoordInt;
+use super::coords::edges::{EdgeCoord, EdgeKey, VertexCoord, VertexKey};
+
+/*
+    Storage for data on the edges and vertices of hexes (rivers, walls, roads...), kept alongside
+    a `HexGrid` holding the hexes themselves
+*/
+
+// Iterators over the features of an `EdgeGrid` or `VertexGrid`, as coordinates with their data
+pub type FeatureIter<'a, K, T, D> = iter::Map<hash_map::Values<'a, K, (T, D)>, fn(&'a (T, D)) -> (&'a T, &'a D)>;
+pub type FeatureIterMut<'a, K, T, D> = iter::Map<hash_map::ValuesMut<'a, K, (T, D)>, fn(&'a mut (T, D)) -> (&'a T, &'a mut D)>;
+
+macro_rules! impl_feature_grid {
+    ($T:ident, $coord:ident, $key:ident, $around_fn:ident) => {
+        pub struct $T<D, I = i32> {
+            features: HashMap<$key<I>, ($coord<I>, D)>
+        }
+
+        impl<D, I: CoordInt> $T<D, I> {
+            pub fn new() -> Self {
+                Self { features: HashMap::new() }
+            }
+
+            // Returns the data previously stored for the same coordinate, if any
+            pub fn insert(&mut self, coord: $coord<I>, data: D) -> Option<D> {
+                self.features.insert(coord.get_key(), (coord, data)).map(|(_, old)| old)
+            }
+
+            pub fn remove(&mut self, coord: &$coord<I>) -> Option<D> {
+                self.features.remove(&coord.get_key()).map(|(_, data)| data)
+            }
+
+            pub fn contains(&self, coord: &$coord<I>) -> bool {
+                self.features.contains_key(&coord.get_key())
+            }
+
+            pub fn borrow_data(&self, coord: &$coord<I>) -> Option<&D> {
+                self.borrow_data_by_key(coord.get_key())
+            }
+
+            pub fn mut_borrow_data(&mut self, coord: &$coord<I>) -> Option<&mut D> {
+                self.mut_borrow_data_by_key(coord.get_key())
+            }
+
+            pub fn borrow_data_by_key(&self, key: $key<I>) -> Option<&D> {
+                self.features.get(&key).map(|(_, data)| data)
+            }
+
+            pub fn mut_borrow_data_by_key(&mut self, key: $key<I>) -> Option<&mut D> {
+                self.features.get_mut(&key).map(|(_, data)| data)
+            }
+
+            pub fn len(&self) -> usize {
+                self.features.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.features.is_empty()
+            }
+
+            pub fn iter(&self) -> FeatureIter<'_, $key<I>, $coord<I>, D> {
+                self.features.values().map(|(coord, data)| (coord, data))
+            }
+
+            pub fn iter_mut(&mut self) -> FeatureIterMut<'_, $key<I>, $coord<I>, D> {
+                self.features.values_mut().map(|(coord, data)| (&*coord, data))
+            }
+
+            // The stored features around a hex, in the same order as the hex's own list
+            pub fn $around_fn<'a, C: CoordType<Int = I>>(&'a self, hex: &C) -> impl Iterator<Item=(&'a $coord<I>, &'a D)> + 'a {
+                IntoIterator::into_iter(hex.$around_fn().map(|c| c.get_key())).filter_map(move |key| {
+                    self.features.get(&key).map(|(coord, data)| (coord, data))
+                })
+            }
+        }
+
+        impl<D, I: CoordInt> Default for $T<D, I> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<'a, D, I: CoordInt> IntoIterator for &'a $T<D, I> {
+            type Item = (&'a $coord<I>, &'a D);
+            type IntoIter = FeatureIter<'a, $key<I>, $coord<I>, D>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl<'a, D, I: CoordInt> IntoIterator for &'a mut $T<D, I> {
+            type Item = (&'a $coord<I>, &'a mut D);
+            type IntoIter = FeatureIterMut<'a, $key<I>, $coord<I>, D>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter_mut()
+            }
+        }
+    }
+}
+
+impl_feature_grid!(EdgeGrid, EdgeCoord, EdgeKey, edges);
+impl_feature_grid!(VertexGrid, VertexCoord, VertexKey, vertices);
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::coords::{CubeCoords, AxialCoords};
+    use super::super::coords::utils::Orientation;
+
+    #[test]
+    fn test_edge_grid() {
+        let orient = Orientation::Flat;
+        let hex = CubeCoords::new((0, 0, 0), orient);
+        let mut walls: EdgeGrid<u8> = EdgeGrid::new();
+        assert!(walls.is_empty());
+
+        let edge = EdgeCoord::new(&hex, 4);
+        assert_eq!(walls.insert(edge, 1), None);
+        // The same edge named from the neighbour replaces the data
+        assert_eq!(walls.insert(EdgeCoord::new(&hex.neighbour(4), 1), 2), Some(1));
+        assert_eq!(walls.len(), 1);
+        assert_eq!(walls.borrow_data(&edge), Some(&2));
+
+        walls.insert(EdgeCoord::new(&hex, 0), 3);
+        walls.insert(EdgeCoord::new(&CubeCoords::new((5, -5, 0), orient), 0), 4);
+        *walls.mut_borrow_data(&edge).unwrap() += 1;
+        let around: Vec<u8> = walls.edges(&hex).map(|(_, d)| *d).collect();
+        assert_eq!(around, vec![3, 3]);
+        assert_eq!(walls.edges(&hex.neighbour(4)).count(), 1);
+
+        assert_eq!(walls.remove(&edge), Some(3));
+        assert!(!walls.contains(&edge));
+        assert_eq!(walls.iter().count(), 2);
+
+        // Looping over the grid gives the same items as `iter`
+        for (coord, data) in &walls {
+            assert_eq!(walls.borrow_data(coord), Some(data));
+        }
+        assert_eq!((&walls).into_iter().collect::<Vec<_>>(), walls.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_vertex_grid() {
+        let orient = Orientation::Pointy;
+        let hex = AxialCoords::new((1, 1), orient);
+        let mut towns: VertexGrid<&str> = VertexGrid::new();
+        for (i, vertex) in hex.vertices().iter().enumerate() {
+            if i % 2 == 0 {
+                towns.insert(*vertex, "town");
+            }
+        }
+        assert_eq!(towns.len(), 3);
+        assert_eq!(towns.vertices(&hex).count(), 3);
+        // Each neighbour shares two of the hex's vertices, one of which has a town
+        for n in hex.neighbours().iter() {
+            assert_eq!(towns.vertices(n).count(), 1);
+        }
+        for (_, name) in &mut towns {
+            *name = "city";
+        }
+        assert!(towns.iter().all(|(_, name)| *name == "city"));
+    }
+}
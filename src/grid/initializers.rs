@@ -1,121 +1,437 @@
-use super::coords::{CoordType, OffsetOddCoords, OffsetEvenCoords, CubeCoords};
-use super::coords::alg::get_range_cube;
-use super::coords::utils::Orientation;
-
-/*
-    Iterators that generate some common map shapes to initialize a grid
-*/
-
-// Square Offset Odd Grid
-
-pub struct SquareFlatOffsetOddGridIterator<C: CoordType> {
-    origin: C,
-    width: i32,
-    height: i32,
-    x: i32,
-    y: i32
-}
-
-impl<C: CoordType> SquareFlatOffsetOddGridIterator<C> {
-    pub fn new(origin: C, width: i32, height: i32) -> Self {
-        Self {
-            origin,
-            width,
-            height,
-            x: 0,
-            y: 0
-        }
-    }
-}
-
-impl<C: CoordType + From<OffsetOddCoords>> Iterator for SquareFlatOffsetOddGridIterator<C> {
-    type Item = C;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.x >= self.width || self.y >= self.height {
-            return None
-        }
-        let res = C::from(OffsetOddCoords::new((self.x, self.y), self.origin.orientation()));
-        self.x += 1;
-        if self.x >= self.width {
-            self.x = 0;
-            self.y += 1;
-        }
-        Some(res)
-    }
-}
-
-// Square Offset Even Grid
-
-pub struct SquareFlatOffsetEvenGridIterator<C: CoordType> {
-    origin: C,
-    width: i32,
-    height: i32,
-    x: i32,
-    y: i32
-}
-
-impl<C: CoordType> SquareFlatOffsetEvenGridIterator<C> {
-    pub fn new(origin: C, width: i32, height: i32) -> Self {
-        Self {
-            origin,
-            width,
-            height,
-            x: 0,
-            y: 0
-        }
-    }
-}
-
-impl<C: CoordType + From<OffsetEvenCoords>> Iterator for SquareFlatOffsetEvenGridIterator<C> {
-    type Item = C;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.x >= self.width || self.y >= self.height {
-            return None
-        }
-        let res = C::from(OffsetEvenCoords::new((self.x, self.y), self.origin.orientation()));
-        self.x += 1;
-        if self.x >= self.width {
-            self.x = 0;
-            self.y += 1;
-        }
-        Some(res)
-    }
-}
-
-// Hexagonal Centered Grid
-
-pub struct HexagonalCenteredGridIterator<C: CoordType> {
-    origin: (i32, i32, i32),
-    orient: Orientation,
-    range: i32,
-    store: Option<Vec<C>>,
-}
-
-impl<C: CoordType> HexagonalCenteredGridIterator<C> {
-    pub fn new(range: i32, orient: Orientation) -> Self {
-        Self {
-            origin: (0, 0, 0),
-            orient,
-            range,
-            store: None
-        }
-    }
-}
-
-impl<C: CoordType + From<CubeCoords>> Iterator for HexagonalCenteredGridIterator<C> {
-    type Item = C;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.store {
-            Some(ref mut list) => list.pop(),
-            None => {
-                let mut list: Vec<C> = get_range_cube(self.origin, self.range).iter().map(|t| C::from(CubeCoords::new(*t, self.orient))).collect();
-                let result = list.pop();
-                self.store = Some(list);
-                result
-            }
-        }
-    }
-}
\ No newline at end of file
+use std::marker::PhantomData;
+
+use super::coords::{CoordType, OffsetOddCoords, OffsetEvenCoords};
+use super::coords::alg::{self, sum_tuple_3, RangeIterator};
+use super::coords::num::CoordInt;
+use super::coords::utils::Orientation;
+
+/*
+    Iterators that generate some common map shapes to initialize a grid
+
+    Every shape is placed relative to its origin, and can be generated in any coordinate system.
+*/
+
+// Square Offset Odd Grid (a rectangle in offset odd coordinates, starting at the origin)
+
+pub struct SquareFlatOffsetOddGridIterator<C: CoordType> {
+    origin: (C::Int, C::Int),
+    orient: Orientation,
+    width: C::Int,
+    height: C::Int,
+    x: C::Int,
+    y: C::Int,
+    _c: PhantomData<C>
+}
+
+impl<C: CoordType> SquareFlatOffsetOddGridIterator<C> {
+    pub fn new(origin: C, width: C::Int, height: C::Int) -> Self {
+        Self {
+            origin: OffsetOddCoords::from_cube(origin.to_cube(), origin.orientation()).get(),
+            orient: origin.orientation(),
+            width,
+            height,
+            x: C::Int::ZERO,
+            y: C::Int::ZERO,
+            _c: PhantomData
+        }
+    }
+}
+
+impl<C: CoordType> Iterator for SquareFlatOffsetOddGridIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.x >= self.width || self.y >= self.height {
+            return None
+        }
+        let offset = OffsetOddCoords::new((self.origin.0.plus(self.x), self.origin.1.plus(self.y)), self.orient);
+        self.x = self.x.plus(C::Int::ONE);
+        if self.x >= self.width {
+            self.x = C::Int::ZERO;
+            self.y = self.y.plus(C::Int::ONE);
+        }
+        Some(C::from_cube(offset.to_cube(), self.orient))
+    }
+}
+
+// Square Offset Even Grid (a rectangle in offset even coordinates, starting at the origin)
+
+pub struct SquareFlatOffsetEvenGridIterator<C: CoordType> {
+    origin: (C::Int, C::Int),
+    orient: Orientation,
+    width: C::Int,
+    height: C::Int,
+    x: C::Int,
+    y: C::Int,
+    _c: PhantomData<C>
+}
+
+impl<C: CoordType> SquareFlatOffsetEvenGridIterator<C> {
+    pub fn new(origin: C, width: C::Int, height: C::Int) -> Self {
+        Self {
+            origin: OffsetEvenCoords::from_cube(origin.to_cube(), origin.orientation()).get(),
+            orient: origin.orientation(),
+            width,
+            height,
+            x: C::Int::ZERO,
+            y: C::Int::ZERO,
+            _c: PhantomData
+        }
+    }
+}
+
+impl<C: CoordType> Iterator for SquareFlatOffsetEvenGridIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.x >= self.width || self.y >= self.height {
+            return None
+        }
+        let offset = OffsetEvenCoords::new((self.origin.0.plus(self.x), self.origin.1.plus(self.y)), self.orient);
+        self.x = self.x.plus(C::Int::ONE);
+        if self.x >= self.width {
+            self.x = C::Int::ZERO;
+            self.y = self.y.plus(C::Int::ONE);
+        }
+        Some(C::from_cube(offset.to_cube(), self.orient))
+    }
+}
+
+// Rectangle Grid
+//
+// Columns of hexes for flat-top orientation and rows of hexes for pointy-top, with the origin in
+// the top left corner. Unlike the offset rectangles the shape is the same wherever the origin is.
+
+pub struct RectangleGridIterator<C: CoordType> {
+    origin: (C::Int, C::Int, C::Int),
+    orient: Orientation,
+    width: C::Int,
+    height: C::Int,
+    x: C::Int,
+    y: C::Int,
+    _c: PhantomData<C>
+}
+
+impl<C: CoordType> RectangleGridIterator<C> {
+    pub fn new(origin: C, width: C::Int, height: C::Int) -> Self {
+        Self {
+            origin: origin.to_cube(),
+            orient: origin.orientation(),
+            width,
+            height,
+            x: C::Int::ZERO,
+            y: C::Int::ZERO,
+            _c: PhantomData
+        }
+    }
+}
+
+impl<C: CoordType> Iterator for RectangleGridIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.x >= self.width || self.y >= self.height {
+            return None
+        }
+        // Every other column (or row) is shifted back half a hex to keep the edges straight
+        let (q, r) = match self.orient {
+            Orientation::Flat => (self.x, self.y.minus(self.x.half())),
+            Orientation::Pointy => (self.x.minus(self.y.half()), self.y)
+        };
+        self.x = self.x.plus(C::Int::ONE);
+        if self.x >= self.width {
+            self.x = C::Int::ZERO;
+            self.y = self.y.plus(C::Int::ONE);
+        }
+        Some(C::from_cube(sum_tuple_3(self.origin, (q, q.negate().minus(r), r)), self.orient))
+    }
+}
+
+// Parallelogram Grid
+
+// The pair of cube axes the sides of a parallelogram run along
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum AxisPair {
+    QR,
+    RS,
+    SQ
+}
+
+pub struct ParallelogramGridIterator<C: CoordType> {
+    origin: (C::Int, C::Int, C::Int),
+    orient: Orientation,
+    axes: AxisPair,
+    width: C::Int,
+    height: C::Int,
+    x: C::Int,
+    y: C::Int,
+    _c: PhantomData<C>
+}
+
+impl<C: CoordType> ParallelogramGridIterator<C> {
+    // `width` hexes along the first axis of the pair and `height` along the second
+    pub fn new(origin: C, axes: AxisPair, width: C::Int, height: C::Int) -> Self {
+        Self {
+            origin: origin.to_cube(),
+            orient: origin.orientation(),
+            axes,
+            width,
+            height,
+            x: C::Int::ZERO,
+            y: C::Int::ZERO,
+            _c: PhantomData
+        }
+    }
+}
+
+impl<C: CoordType> Iterator for ParallelogramGridIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.x >= self.width || self.y >= self.height {
+            return None
+        }
+        let (a, b) = (self.x, self.y);
+        // Cube coordinates are stored as (q, s, r)
+        let offset = match self.axes {
+            AxisPair::QR => (a, a.negate().minus(b), b),
+            AxisPair::RS => (a.negate().minus(b), b, a),
+            AxisPair::SQ => (b, a, a.negate().minus(b))
+        };
+        self.x = self.x.plus(C::Int::ONE);
+        if self.x >= self.width {
+            self.x = C::Int::ZERO;
+            self.y = self.y.plus(C::Int::ONE);
+        }
+        Some(C::from_cube(sum_tuple_3(self.origin, offset), self.orient))
+    }
+}
+
+// Triangle Grid
+
+// Which way a triangle points for pointy-top hexes, flat-top triangles point right (Down) and left (Up)
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TrianglePoint {
+    Down,
+    Up
+}
+
+pub struct TriangleGridIterator<C: CoordType> {
+    origin: (C::Int, C::Int, C::Int),
+    orient: Orientation,
+    point: TrianglePoint,
+    size: C::Int,
+    q: C::Int,
+    r: C::Int,
+    _c: PhantomData<C>
+}
+
+impl<C: CoordType> TriangleGridIterator<C> {
+    // Triangles with `size + 1` hexes along each side, the origin is the top left corner of the
+    // triangle's bounding parallelogram
+    pub fn new(origin: C, point: TrianglePoint, size: C::Int) -> Self {
+        let size = size.magnitude();
+        Self {
+            origin: origin.to_cube(),
+            orient: origin.orientation(),
+            point,
+            size,
+            q: C::Int::ZERO,
+            r: Self::first_r(point, size, C::Int::ZERO),
+            _c: PhantomData
+        }
+    }
+
+    fn first_r(point: TrianglePoint, size: C::Int, q: C::Int) -> C::Int {
+        match point {
+            TrianglePoint::Down => C::Int::ZERO,
+            TrianglePoint::Up => size.minus(q)
+        }
+    }
+
+    fn last_r(&self) -> C::Int {
+        match self.point {
+            TrianglePoint::Down => self.size.minus(self.q),
+            TrianglePoint::Up => self.size
+        }
+    }
+}
+
+impl<C: CoordType> Iterator for TriangleGridIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.q > self.size {
+            return None
+        }
+        let (q, r) = (self.q, self.r);
+        if self.r >= self.last_r() {
+            self.q = self.q.plus(C::Int::ONE);
+            self.r = Self::first_r(self.point, self.size, self.q);
+        } else {
+            self.r = self.r.plus(C::Int::ONE);
+        }
+        Some(C::from_cube(sum_tuple_3(self.origin, (q, q.negate().minus(r), r)), self.orient))
+    }
+}
+
+// Hexagonal Centered Grid (walked row by row, see `alg::range`)
+
+pub struct HexagonalCenteredGridIterator<C: CoordType> {
+    hexes: RangeIterator<C>
+}
+
+impl<C: CoordType> HexagonalCenteredGridIterator<C> {
+    pub fn new(range: C::Int, orient: Orientation) -> Self {
+        Self::centered_at(&C::from_cube((C::Int::ZERO, C::Int::ZERO, C::Int::ZERO), orient), range)
+    }
+
+    pub fn centered_at(center: &C, range: C::Int) -> Self {
+        Self {
+            hexes: alg::range(center, range)
+        }
+    }
+}
+
+impl<C: CoordType> Iterator for HexagonalCenteredGridIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.hexes.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.hexes.size_hint()
+    }
+}
+
+impl<C: CoordType> ExactSizeIterator for HexagonalCenteredGridIterator<C> {}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use super::super::coords::{CubeCoords, AxialCoords, CoordKey};
+    use super::super::coords::utils::HexShape;
+
+    fn keys<C: CoordType<Int = i32>, I: Iterator<Item=C>>(iter: I) -> Vec<CoordKey> {
+        let keys: Vec<CoordKey> = iter.map(|c| c.get_key()).collect();
+        assert_eq!(keys.iter().collect::<HashSet<_>>().len(), keys.len(), "shape contains duplicates");
+        keys
+    }
+
+    fn translated(keys: &[CoordKey], by: (i32, i32, i32)) -> HashSet<CoordKey> {
+        keys.iter().map(|k| {
+            let (x, y, z) = CubeCoords::from_key(*k, Orientation::Flat).get();
+            CoordKey::from((x + by.0, y + by.1, z + by.2))
+        }).collect()
+    }
+
+    #[test]
+    fn test_offset_rectangles_honor_origin() {
+        for orient in [Orientation::Flat, Orientation::Pointy].iter() {
+            let origin = OffsetOddCoords::new((3, 4), *orient);
+            let coords: Vec<OffsetOddCoords> = SquareFlatOffsetOddGridIterator::new(origin, 3, 2).collect();
+            assert_eq!(coords.len(), 6);
+            assert_eq!(coords[0], origin);
+            assert_eq!(coords[5].get(), (5, 5));
+
+            let origin = OffsetEvenCoords::new((-2, 1), *orient);
+            let coords: Vec<OffsetEvenCoords> = SquareFlatOffsetEvenGridIterator::new(origin, 2, 2).collect();
+            assert_eq!(coords.iter().map(|c| c.get()).collect::<Vec<_>>(), vec![(-2, 1), (-1, 1), (-2, 2), (-1, 2)]);
+
+            // Other coordinate systems get the same hexes
+            let cubes: Vec<CubeCoords> = SquareFlatOffsetOddGridIterator::new(CubeCoords::from(&OffsetOddCoords::new((3, 4), *orient)), 3, 2).collect();
+            let offsets: Vec<OffsetOddCoords> = SquareFlatOffsetOddGridIterator::new(OffsetOddCoords::new((3, 4), *orient), 3, 2).collect();
+            assert_eq!(cubes.iter().map(OffsetOddCoords::from).collect::<Vec<_>>(), offsets);
+        }
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let flat: Vec<CubeCoords> = RectangleGridIterator::new(CubeCoords::new((0, 0, 0), Orientation::Flat), 4, 3).collect();
+        assert_eq!(keys(flat.iter().cloned()).len(), 12);
+        // Flat-top columns keep the top edge level
+        let tops: Vec<_> = flat.iter().take(4).map(|c| c.to_pixel(HexShape::new(1.0f32, Orientation::Flat)).get().1).collect();
+        assert!(tops.iter().all(|y| (*y - tops[0]).abs() < 0.9));
+
+        let pointy: Vec<AxialCoords> = RectangleGridIterator::new(AxialCoords::new((0, 0), Orientation::Pointy), 4, 3).collect();
+        assert_eq!(pointy.len(), 12);
+        let lefts: Vec<_> = pointy.iter().step_by(4).map(|c| c.to_pixel(HexShape::new(1.0f32, Orientation::Pointy)).get().0).collect();
+        assert!(lefts.iter().all(|x| (*x - lefts[0]).abs() < 0.9));
+
+        // Moving the origin moves the whole shape, even onto an odd row or column
+        for orient in [Orientation::Flat, Orientation::Pointy].iter() {
+            let base = keys(RectangleGridIterator::new(CubeCoords::new((0, 0, 0), *orient), 5, 4));
+            let moved = keys(RectangleGridIterator::new(CubeCoords::new((1, 1, -2), *orient), 5, 4));
+            assert_eq!(moved.into_iter().collect::<HashSet<_>>(), translated(&base, (1, 1, -2)));
+        }
+    }
+
+    #[test]
+    fn test_parallelogram() {
+        let origin = CubeCoords::new((1, -1, 0), Orientation::Pointy);
+        for axes in [AxisPair::QR, AxisPair::RS, AxisPair::SQ].iter() {
+            let coords: Vec<CubeCoords> = ParallelogramGridIterator::new(origin, *axes, 3, 2).collect();
+            assert_eq!(keys(coords.iter().cloned()).len(), 6);
+            assert_eq!(coords[0], origin);
+            // The far corner is the sum of both sides
+            assert_eq!(origin.distance(&coords[5]), 3);
+        }
+        let qr: Vec<CubeCoords> = ParallelogramGridIterator::new(origin, AxisPair::QR, 2, 2).collect();
+        assert_eq!(qr.iter().map(|c| c.get()).collect::<Vec<_>>(), vec![(1, -1, 0), (2, -2, 0), (1, -2, 1), (2, -3, 1)]);
+        // The three pairings are different shapes
+        let rs = keys(ParallelogramGridIterator::new(origin, AxisPair::RS, 2, 2));
+        let sq = keys(ParallelogramGridIterator::new(origin, AxisPair::SQ, 2, 2));
+        assert_ne!(keys(qr.into_iter()).into_iter().collect::<HashSet<_>>(), rs.iter().cloned().collect());
+        assert_ne!(rs.into_iter().collect::<HashSet<_>>(), sq.into_iter().collect());
+    }
+
+    #[test]
+    fn test_triangle() {
+        let origin = CubeCoords::new((0, 0, 0), Orientation::Pointy);
+        let down = keys(TriangleGridIterator::new(origin, TrianglePoint::Down, 3));
+        let up = keys(TriangleGridIterator::new(origin, TrianglePoint::Up, 3));
+        assert_eq!(down.len(), 10);
+        assert_eq!(up.len(), 10);
+        assert!(down.contains(&origin.get_key()));
+        assert!(!up.contains(&origin.get_key()));
+        // Together they fill the parallelogram, overlapping along the diagonal
+        let mut both: HashSet<CoordKey> = down.iter().cloned().collect();
+        both.extend(up.iter().cloned());
+        let parallelogram = keys(ParallelogramGridIterator::new(origin, AxisPair::QR, 4, 4));
+        assert_eq!(both, parallelogram.into_iter().collect());
+
+        let single: Vec<CubeCoords> = TriangleGridIterator::new(origin, TrianglePoint::Up, 0).collect();
+        assert_eq!(single, vec![origin]);
+
+        let moved = keys(TriangleGridIterator::new(CubeCoords::new((2, 0, -2), Orientation::Pointy), TrianglePoint::Down, 3));
+        assert_eq!(moved.into_iter().collect::<HashSet<_>>(), translated(&down, (2, 0, -2)));
+    }
+
+    #[test]
+    fn test_hexagon() {
+        assert_eq!(HexagonalCenteredGridIterator::<CubeCoords>::new(3, Orientation::Flat).count(), 37);
+        // Any coordinate system can be generated, not just cube coordinates
+        let axial: Vec<AxialCoords> = HexagonalCenteredGridIterator::new(2, Orientation::Flat).collect();
+        assert_eq!(axial.len(), 19);
+
+        let center = OffsetOddCoords::new((4, 3), Orientation::Pointy);
+        let coords: Vec<OffsetOddCoords> = HexagonalCenteredGridIterator::centered_at(&center, 2).collect();
+        assert_eq!(coords.len(), 19);
+        assert!(coords.contains(&center));
+        assert!(coords.iter().all(|c| c.distance(&center) <= 2));
+
+        // The size is known up front, and hexes come out row by row starting from the top
+        let mut iter = HexagonalCenteredGridIterator::<CubeCoords>::new(1000, Orientation::Pointy);
+        assert_eq!(iter.len(), 3003001);
+        assert_eq!(iter.next().unwrap().get(), (0, 1000, -1000));
+        assert_eq!(iter.len(), 3003000);
+        let cubes: Vec<(i32, i32, i32)> = HexagonalCenteredGridIterator::<CubeCoords>::new(1, Orientation::Flat).map(|c| c.get()).collect();
+        assert_eq!(cubes, vec![(0, 1, -1), (1, 0, -1), (-1, 1, 0), (0, 0, 0), (1, -1, 0), (-1, 0, 1), (0, -1, 1)]);
+    }
+}
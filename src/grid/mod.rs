@@ -1,236 +1,1127 @@
-use std::collections::HashMap;
-use std::marker::PhantomData;
-
-pub mod coords;
-pub mod tile;
-pub mod initializers;
-
-use self::tile::{HexTile};
-use self::coords::utils::HexShape;
-
-// Need to import CoordType to use the trait's function on the apllicable objects
-use self::coords::{CoordType, CoordKey};
-
-pub trait HexGridDataWrapper<C: CoordType, D> {
-    fn new() -> Self;
-    fn initialize<I>(&mut self, init: I, new_data: &Fn() -> D) 
-        where I: Iterator<Item=C>;
-
-    fn borrow_tile_by_key(&self, key: CoordKey) -> Option<&HexTile<C, D>>;
-    fn mut_borrow_tile_by_key(&mut self, key: CoordKey) -> Option<&mut HexTile<C, D>>;
-
-    fn mutate_all_data(&mut self, f: &Fn(&mut D));
-}
-
-// pub struct HexGrid2DVecWrapper<C: CoordType, D> {
-//     tiles: Vec<Vec<HexTile<C, D>>>
-// }
-
-// impl<C: CoordType, D> HexGridDataWrapper<C, D> for HexGrid2DVecWrapper<C, D> {
-//     fn borrow_tile_by_key(&self, key: CoordKey) -> Option<&HexTile<C, D>> {
-//         let (x, y) = key.to_array_idxs();
-//         if x >= self.tiles.len() || y >= self.tiles[x].len() {
-//             return None
-//         }
-//         Some(&self.tiles[x][y])
-//     }
-
-//     fn mut_borrow_tile_by_key(&mut self, key: CoordKey) -> Option<&mut HexTile<C, D>> {
-//         let (x, y) = key.to_array_idxs();
-//         if x >= self.tiles.len() || y >= self.tiles[x].len() {
-//             return None
-//         }
-//         Some(&mut self.tiles[x][y])
-//     }
-
-//     fn mutate_all_data(&mut self, f: &Fn(&mut D)) {
-//         for col in self.tiles.iter_mut() {
-//             for t in col.iter_mut() {
-//                 t.mutate_data(f);
-//             }
-//         }
-//     }
-// }
-
-
-pub struct HexGridHashMapWrapper<C: CoordType, D> {
-    tiles: HashMap<CoordKey, HexTile<C, D>>
-}
-
-impl<C: CoordType, D> HexGridDataWrapper<C, D> for HexGridHashMapWrapper<C, D> {
-    fn new() -> Self {
-        Self {
-            tiles: HashMap::new()
-        }
-    }
-
-    fn initialize<I>(&mut self, init: I, new_data: &Fn() -> D) 
-            where I: Iterator<Item=C> {
-        for coord in init {
-            self.tiles.insert(coord.get_key(), HexTile::new(coord, new_data()));
-        }
-    }
-
-    fn borrow_tile_by_key(&self, key: CoordKey) -> Option<&HexTile<C, D>> {
-        self.tiles.get(&key)
-    }
-
-    fn mut_borrow_tile_by_key(&mut self, key: CoordKey) -> Option<&mut HexTile<C, D>> {
-        self.tiles.get_mut(&key)
-    }
-
-    fn mutate_all_data(&mut self, f: &Fn(&mut D)) {
-        for (_, tile) in self.tiles.iter_mut() {
-            tile.mutate_data(f);
-        }
-    }
-}
-
-impl<'a, C: CoordType, D> IntoIterator for &'a HexGridHashMapWrapper<C, D> {
-    type Item = &'a HexTile<C, D>;
-    type IntoIter = ::std::collections::hash_map::Values<'a, CoordKey, HexTile<C, D>>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.tiles.values()
-    }
-}
-
-/*
-    Define outer grid construct
-*/
-
-pub struct HexGrid<C: CoordType, D, W: HexGridDataWrapper<C, D>> {
-    pub wrapper: W,
-    hexshape: HexShape,
-    _c: PhantomData<C>,
-    _d: PhantomData<D>
-}
-
-impl<C: CoordType, D, W: HexGridDataWrapper<C, D>> HexGrid<C, D, W> {
-    pub fn new(hexshape: HexShape, init: Option<&Fn() -> W>) -> Self {
-        match init {
-            Some(f) => Self {
-                wrapper: f(),
-                hexshape,
-                _c: PhantomData,
-                _d: PhantomData
-            },
-            None => Self {
-                wrapper: W::new(),
-                hexshape,
-                _c: PhantomData,
-                _d: PhantomData
-            }
-        }
-    }
-
-    pub fn initialize<I>(&mut self, init: I, new_data: &Fn() -> D) 
-            where I: Iterator<Item=C> {
-        self.wrapper.initialize(init, new_data)
-    }
-
-    pub fn borrow_tile_by_key(&self, key: CoordKey) -> Option<&HexTile<C, D>> {
-        self.wrapper.borrow_tile_by_key(key)
-    }
-
-    pub fn mut_borrow_tile_by_key(&mut self, key: CoordKey) -> Option<&mut HexTile<C, D>> {
-        self.wrapper.mut_borrow_tile_by_key(key)
-    }
-
-    pub fn borrow_data_by_key(&self, key: CoordKey) -> Option<&D> {
-        match self.borrow_tile_by_key(key) {
-            Some(tile) => Some(tile.borrow_data()),
-            None => None
-        }
-    }
-
-    pub fn mut_borrow_data_by_key(&mut self, key: CoordKey) -> Option<&mut D> {
-        match self.mut_borrow_tile_by_key(key) {
-            Some(tile) => Some(tile.mut_borrow_data()),
-            None => None
-        }
-    }
-
-    pub fn mutate_all_data(&mut self, f: &Fn(&mut D)) {
-        self.wrapper.mutate_all_data(f)
-    }
-
-    pub fn mut_borrow_data_for_pixel(&mut self, (x, y): (f32, f32)) -> Option<&mut D> {
-        let key = C::from_pixel(&coords::PixelCoord::new((x, y), self.hexshape)).get_key();
-        self.mut_borrow_data_by_key(key)
-    }
-}
-
-/*
-    Allow HexGrid to cast into an iterator of all its items
-*/
-
-// Helpers
-
-// fn get_coord_from_1d_index<C: CoordType, D>(grid: &HexGrid<C, D>, i: usize) -> (usize, usize) {
-//     let (mut x, mut y): (usize, usize) = (0, i);
-//     while x < grid.tiles.len() && y >= grid.tiles[x].len() {
-//         y -= grid.tiles[x].len();
-//         x += 1;
-//     }
-//     (x, y)
-// }
-
-// Immutable Iteration
-
-// impl<'a, C: CoordType, D> IntoIterator for &'a HexGrid<C, D> {
-//     type Item = &'a HexTile<C, D>;
-//     type IntoIter = HexGridTileIterator<'a, C, D>;
-
-//     fn into_iter(self) -> Self::IntoIter {
-//         HexGridTileIterator { grid: self, index: 0 }
-//     }
-// }
-
-// pub struct HexGridTileIterator<'a, C: CoordType + 'a, D: 'a> {
-//     grid: &'a HexGrid<C, D>,
-//     index: usize
-// }
-
-// impl<'a, C: CoordType + 'a, D: 'a> Iterator for HexGridTileIterator<'a, C, D> {
-//     type Item = &'a HexTile<C, D>;
-
-//     fn next(&mut self) -> Option<&'a HexTile<C, D>> {
-//         let result = get_coord_from_1d_index(self.grid, self.index);
-//         self.index += 1;
-//         self.grid.borrow_tile(result)
-//     }
-// }
-
-// Mutable iteration (Might be impossible without hacks)
-
-// impl<'a> IntoIterator for &'a mut HexGrid {
-//     type Item = &'a mut HexTile<coords::OffsetOddCoordsFlat, SomeData>;
-//     type IntoIter = HexGridTileIteratorMut<'a>;
-
-//     fn into_iter(self) -> Self::IntoIter {
-//         HexGridTileIteratorMut { grid: self, index: 0 }
-//     }
-// }
-
-// pub struct HexGridTileIteratorMut<'a> {
-//     grid: &'a mut HexGrid,
-//     index: usize
-// }
-
-// impl<'a> HexGridTileIteratorMut<'a> {
-//     fn get(&mut self, r: (usize, usize)) -> Option<&mut HexTile<coords::OffsetOddCoordsFlat, SomeData>> {
-//         self.grid.mut_borrow_tile(r)
-//     }
-// }
-
-// impl<'a> Iterator for HexGridTileIteratorMut<'a> {
-//     type Item = &'a mut HexTile<coords::OffsetOddCoordsFlat, SomeData>;
-
-//     fn next(&mut self) -> Option<&'a mut HexTile<coords::OffsetOddCoordsFlat, SomeData>> {
-//         let result = get_coord_from_1d_index(self.grid, self.index);
-//         self.index += 1;
-//         // self.grid.mut_borrow_tile(result)
-//         self.get(result)
-//     }
-// }
\ No newline at end of file
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+
+pub mod coords;
+pub mod tile;
+pub mod initializers;
+pub mod pathfinding;
+pub mod visibility;
+pub mod regions;
+pub mod shape;
+pub mod topology;
+pub mod features;
+
+use self::tile::{HexTile, HexTileRef, HexTileMut};
+use self::coords::utils::{HexShape, Orientation, Layout};
+
+// Need to import CoordType to use the trait's function on the apllicable objects
+use self::coords::{CoordType, CoordKey, OffsetOddCoords};
+use self::coords::num::{self, CoordInt, CoordFloat};
+use self::pathfinding::SearchResult;
+use self::visibility::FovMode;
+use self::regions::Components;
+use self::topology::Topology;
+
+pub trait HexGridDataWrapper<C: CoordType, D> {
+    type Iter<'a>: Iterator<Item=HexTileRef<'a, C, D>> where Self: 'a, C: 'a, D: 'a;
+    type IterMut<'a>: Iterator<Item=HexTileMut<'a, C, D>> where Self: 'a, C: 'a, D: 'a;
+
+    // The same kind of wrapper storing a different type of data
+    type Mapped<E>: HexGridDataWrapper<C, E>;
+
+    fn new() -> Self;
+
+    // An empty wrapper with the same shape as this one (e.g. the same bounds for a dense wrapper),
+    // so that it can store every tile this one can
+    fn empty_like<E>(&self) -> Self::Mapped<E>;
+
+    // Coordinates which can't be stored (e.g. they're out of bounds) are returned as the error,
+    // every other coordinate is still initialized
+    fn initialize<I>(&mut self, init: I, new_data: &Fn() -> D) -> Result<(), Vec<C>>
+        where I: Iterator<Item=C>;
+
+    fn borrow_tile_by_key(&self, key: CoordKey<C::Int>) -> Option<HexTileRef<'_, C, D>>;
+    fn mut_borrow_tile_by_key(&mut self, key: CoordKey<C::Int>) -> Option<HexTileMut<'_, C, D>>;
+
+    // Borrow several tiles mutably at once. Keys of tiles that don't exist are None, as are
+    // repeats of a key given earlier (each tile is only borrowed once)
+    fn mut_borrow_tiles_by_keys<const N: usize>(&mut self, keys: [CoordKey<C::Int>; N]) -> [Option<HexTileMut<'_, C, D>>; N];
+
+    // Returns false if the tile can't be stored (e.g. it's out of bounds)
+    fn insert_tile(&mut self, tile: HexTile<C, D>) -> bool;
+
+    fn mutate_all_data(&mut self, f: &Fn(&mut D));
+
+    fn iter<'a>(&'a self) -> Self::Iter<'a>;
+    fn iter_mut<'a>(&'a mut self) -> Self::IterMut<'a>;
+}
+
+// Mutably borrow the elements at several indices of a slice at once, alongside their index.
+// Only the first of a repeated index is borrowed, the repeats are None like missing indices
+fn disjoint_mut<T, const N: usize>(slice: &mut [T], indices: [Option<usize>; N]) -> [Option<(usize, &mut T)>; N] {
+    // Missing indices are sorted to the end
+    let mut order = [(usize::MAX, 0); N];
+    for (pos, index) in indices.iter().enumerate() {
+        order[pos] = (index.unwrap_or(usize::MAX), pos);
+    }
+    order.sort_unstable();
+
+    let mut result: [Option<(usize, &mut T)>; N] = ::std::array::from_fn(|_| None);
+    let (mut rest, mut offset) = (slice, 0);
+    for &(i, pos) in order.iter() {
+        if i == usize::MAX {
+            break
+        }
+        if i < offset {
+            continue
+        }
+        let (_, tail) = ::std::mem::take(&mut rest).split_at_mut(i - offset);
+        let (item, tail) = tail.split_first_mut().unwrap();
+        result[pos] = Some((i, item));
+        rest = tail;
+        offset = i + 1;
+    }
+    result
+}
+
+/*
+    Dense storage for fixed map shapes, using the Red Blob Games map storage layouts
+
+    Both wrappers are sized up front (use `HexGrid::new` with an init function to construct them)
+    and only store the data of each tile, the coordinate is rebuilt from its position in storage.
+    Coordinates outside of the bounds are returned as an error by `initialize` and return `None`
+    when borrowed.
+*/
+
+// Where each coordinate of a dense wrapper is stored
+#[derive(Clone, Debug, PartialEq)]
+enum DenseBounds<I> {
+    // Indexed by offset odd coordinates (matching `SquareFlatOffsetOddGridIterator`)
+    Rectangle { width: I, height: I, orientation: Orientation },
+    // Stored row by row (by axial r) relative to the center, with each row only as long as it needs to be
+    Hexagon { center: (I, I, I), radius: I, row_starts: Vec<usize>, orientation: Orientation }
+}
+
+// Positions within the bounds are worked out in `i128`, which holds the offset between any two values
+// of every coordinate integer type
+impl<I: CoordInt> DenseBounds<I> {
+    fn orientation(&self) -> Orientation {
+        match *self {
+            DenseBounds::Rectangle { orientation, .. } | DenseBounds::Hexagon { orientation, .. } => orientation
+        }
+    }
+
+    fn index<C: CoordType<Int = I>>(&self, key: CoordKey<I>) -> Option<usize> {
+        let cube = C::from_key(key, self.orientation()).to_cube();
+        match *self {
+            DenseBounds::Rectangle { width, height, orientation } => {
+                let (col, row) = OffsetOddCoords::from_cube(cube, orientation).get();
+                if col < I::ZERO || row < I::ZERO || col >= width || row >= height {
+                    return None
+                }
+                Some((row.to_i128() * width.to_i128() + col.to_i128()) as usize)
+            },
+            DenseBounds::Hexagon { center: (cx, cy, cz), radius, ref row_starts, .. } => {
+                let (x, y, z) = cube;
+                let (q, r, s) = (x.to_i128() - cx.to_i128(), z.to_i128() - cz.to_i128(), y.to_i128() - cy.to_i128());
+                let radius = radius.to_i128();
+                if q.abs() > radius || r.abs() > radius || s.abs() > radius {
+                    return None
+                }
+                Some(row_starts[(r + radius) as usize] + (q + radius + r.min(0)) as usize)
+            }
+        }
+    }
+
+    fn coord<C: CoordType<Int = I>>(&self, index: usize) -> C {
+        let int = |value: i128| I::from_i128(value).unwrap_or_else(|| num::overflow());
+        let cube = match *self {
+            DenseBounds::Rectangle { width, orientation, .. } => {
+                let (index, width) = (index as i128, width.to_i128());
+                OffsetOddCoords::new((int(index % width), int(index / width)), orientation).to_cube()
+            },
+            DenseBounds::Hexagon { center: (cx, cy, cz), radius, ref row_starts, .. } => {
+                let row = row_starts.partition_point(|start| *start <= index) - 1;
+                let r = row as i128 - radius.to_i128();
+                let q = (index - row_starts[row]) as i128 - radius.to_i128() - r.min(0);
+                (int(q + cx.to_i128()), int(-q - r + cy.to_i128()), int(r + cz.to_i128()))
+            }
+        };
+        C::from_cube(cube, self.orientation())
+    }
+}
+
+// Iterators over the tiles of the dense wrappers
+
+pub struct DenseIter<'a, C: CoordType, D: 'a> {
+    tiles: ::std::iter::Enumerate<::std::slice::Iter<'a, Option<D>>>,
+    bounds: &'a DenseBounds<C::Int>,
+    _c: PhantomData<C>
+}
+
+impl<'a, C: CoordType, D> Iterator for DenseIter<'a, C, D> {
+    type Item = HexTileRef<'a, C, D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bounds = self.bounds;
+        self.tiles.find_map(|(i, data)| data.as_ref().map(|data| HexTileRef::new(bounds.coord(i), data)))
+    }
+}
+
+pub struct DenseIterMut<'a, C: CoordType, D: 'a> {
+    tiles: ::std::iter::Enumerate<::std::slice::IterMut<'a, Option<D>>>,
+    bounds: &'a DenseBounds<C::Int>,
+    _c: PhantomData<C>
+}
+
+impl<'a, C: CoordType, D> Iterator for DenseIterMut<'a, C, D> {
+    type Item = HexTileMut<'a, C, D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bounds = self.bounds;
+        self.tiles.find_map(|(i, data)| data.as_mut().map(|data| HexTileMut::new(bounds.coord(i), data)))
+    }
+}
+
+// Rectangular maps
+
+pub struct HexGridRectangleWrapper<C: CoordType, D> {
+    tiles: Vec<Option<D>>,
+    bounds: DenseBounds<C::Int>,
+    _c: PhantomData<C>
+}
+
+impl<C: CoordType, D> HexGridRectangleWrapper<C, D> {
+    pub fn with_size(width: C::Int, height: C::Int, orientation: Orientation) -> Self {
+        let (width, height) = (width.max(C::Int::ZERO), height.max(C::Int::ZERO));
+        Self {
+            tiles: (0..width.to_i128() * height.to_i128()).map(|_| None).collect(),
+            bounds: DenseBounds::Rectangle { width, height, orientation },
+            _c: PhantomData
+        }
+    }
+
+    pub fn size(&self) -> (C::Int, C::Int) {
+        match self.bounds {
+            DenseBounds::Rectangle { width, height, .. } => (width, height),
+            _ => unreachable!()
+        }
+    }
+}
+
+// Hexagonal maps
+
+pub struct HexGridHexagonWrapper<C: CoordType, D> {
+    tiles: Vec<Option<D>>,
+    bounds: DenseBounds<C::Int>,
+    _c: PhantomData<C>
+}
+
+impl<C: CoordType, D> HexGridHexagonWrapper<C, D> {
+    pub fn with_radius(radius: C::Int, orientation: Orientation) -> Self {
+        Self::with_center((C::Int::ZERO, C::Int::ZERO, C::Int::ZERO), radius, orientation)
+    }
+
+    pub fn centered_at(center: &C, radius: C::Int) -> Self {
+        Self::with_center(center.to_cube(), radius, center.orientation())
+    }
+
+    fn with_center(center: (C::Int, C::Int, C::Int), radius: C::Int, orientation: Orientation) -> Self {
+        let radius = radius.magnitude();
+        let rows = radius.to_i128();
+        let mut row_starts = Vec::with_capacity((2 * rows + 1) as usize);
+        let mut total = 0;
+        for r in -rows..(rows + 1) {
+            row_starts.push(total);
+            total += (2 * rows + 1 - r.abs()) as usize;
+        }
+        Self {
+            tiles: (0..total).map(|_| None).collect(),
+            bounds: DenseBounds::Hexagon { center, radius, row_starts, orientation },
+            _c: PhantomData
+        }
+    }
+
+    pub fn radius(&self) -> C::Int {
+        match self.bounds {
+            DenseBounds::Hexagon { radius, .. } => radius,
+            _ => unreachable!()
+        }
+    }
+}
+
+macro_rules! impl_dense_wrapper {
+    ($($type:ident => $empty:expr),*) => {
+        $(
+            impl<C: CoordType, D> HexGridDataWrapper<C, D> for $type<C, D> {
+                type Iter<'a> = DenseIter<'a, C, D> where C: 'a, D: 'a;
+                type IterMut<'a> = DenseIterMut<'a, C, D> where C: 'a, D: 'a;
+
+                type Mapped<E> = $type<C, E>;
+
+                fn new() -> Self {
+                    $empty
+                }
+
+                fn empty_like<E>(&self) -> Self::Mapped<E> {
+                    $type {
+                        tiles: self.tiles.iter().map(|_| None).collect(),
+                        bounds: self.bounds.clone(),
+                        _c: PhantomData
+                    }
+                }
+
+                fn initialize<I>(&mut self, init: I, new_data: &Fn() -> D) -> Result<(), Vec<C>>
+                        where I: Iterator<Item=C> {
+                    let mut outside = Vec::new();
+                    for coord in init {
+                        match self.bounds.index::<C>(coord.get_key()) {
+                            Some(i) => self.tiles[i] = Some(new_data()),
+                            None => outside.push(coord)
+                        }
+                    }
+                    if outside.is_empty() { Ok(()) } else { Err(outside) }
+                }
+
+                fn borrow_tile_by_key(&self, key: CoordKey<C::Int>) -> Option<HexTileRef<'_, C, D>> {
+                    let i = self.bounds.index::<C>(key)?;
+                    self.tiles[i].as_ref().map(|data| HexTileRef::new(self.bounds.coord(i), data))
+                }
+
+                fn mut_borrow_tile_by_key(&mut self, key: CoordKey<C::Int>) -> Option<HexTileMut<'_, C, D>> {
+                    let i = self.bounds.index::<C>(key)?;
+                    let coord = self.bounds.coord(i);
+                    self.tiles[i].as_mut().map(|data| HexTileMut::new(coord, data))
+                }
+
+                fn mut_borrow_tiles_by_keys<const N: usize>(&mut self, keys: [CoordKey<C::Int>; N]) -> [Option<HexTileMut<'_, C, D>>; N] {
+                    let indices = keys.map(|key| self.bounds.index::<C>(key));
+                    let bounds = &self.bounds;
+                    disjoint_mut(&mut self.tiles, indices).map(|borrow| {
+                        borrow.and_then(|(i, data)| data.as_mut().map(|data| HexTileMut::new(bounds.coord(i), data)))
+                    })
+                }
+
+                fn insert_tile(&mut self, tile: HexTile<C, D>) -> bool {
+                    match self.bounds.index::<C>(tile.get_key()) {
+                        Some(i) => {
+                            self.tiles[i] = Some(tile.into_data());
+                            true
+                        },
+                        None => false
+                    }
+                }
+
+                fn mutate_all_data(&mut self, f: &Fn(&mut D)) {
+                    for data in self.tiles.iter_mut().flatten() {
+                        f(data);
+                    }
+                }
+
+                fn iter<'a>(&'a self) -> Self::Iter<'a> {
+                    DenseIter { tiles: self.tiles.iter().enumerate(), bounds: &self.bounds, _c: PhantomData }
+                }
+
+                fn iter_mut<'a>(&'a mut self) -> Self::IterMut<'a> {
+                    DenseIterMut { tiles: self.tiles.iter_mut().enumerate(), bounds: &self.bounds, _c: PhantomData }
+                }
+            }
+
+            impl<'a, C: CoordType, D> IntoIterator for &'a $type<C, D> {
+                type Item = HexTileRef<'a, C, D>;
+                type IntoIter = DenseIter<'a, C, D>;
+
+                fn into_iter(self) -> Self::IntoIter {
+                    self.iter()
+                }
+            }
+        )*
+    }
+}
+
+impl_dense_wrapper!(
+    HexGridRectangleWrapper => Self::with_size(C::Int::ZERO, C::Int::ZERO, Orientation::Flat),
+    HexGridHexagonWrapper => Self::with_radius(C::Int::ZERO, Orientation::Flat)
+);
+
+// Tiles are stored in the order they're first added, with their positions looked up by key
+
+pub struct HexGridHashMapWrapper<C: CoordType, D> {
+    tiles: Vec<HexTile<C, D>>,
+    indices: HashMap<CoordKey<C::Int>, usize>
+}
+
+// Iterators over the tiles of a `HexGridHashMapWrapper`
+pub type HashMapIter<'a, C, D> = ::std::iter::Map<::std::slice::Iter<'a, HexTile<C, D>>,
+    fn(&'a HexTile<C, D>) -> HexTileRef<'a, C, D>>;
+pub type HashMapIterMut<'a, C, D> = ::std::iter::Map<::std::slice::IterMut<'a, HexTile<C, D>>,
+    fn(&'a mut HexTile<C, D>) -> HexTileMut<'a, C, D>>;
+
+impl<C: CoordType, D> HexGridDataWrapper<C, D> for HexGridHashMapWrapper<C, D> {
+    type Iter<'a> = HashMapIter<'a, C, D> where C: 'a, D: 'a;
+    type IterMut<'a> = HashMapIterMut<'a, C, D> where C: 'a, D: 'a;
+
+    type Mapped<E> = HexGridHashMapWrapper<C, E>;
+
+    fn new() -> Self {
+        Self {
+            tiles: Vec::new(),
+            indices: HashMap::new()
+        }
+    }
+
+    fn empty_like<E>(&self) -> Self::Mapped<E> {
+        HexGridHashMapWrapper::new()
+    }
+
+    fn initialize<I>(&mut self, init: I, new_data: &Fn() -> D) -> Result<(), Vec<C>>
+            where I: Iterator<Item=C> {
+        for coord in init {
+            self.insert_tile(HexTile::new(coord, new_data()));
+        }
+        Ok(())
+    }
+
+    fn borrow_tile_by_key(&self, key: CoordKey<C::Int>) -> Option<HexTileRef<'_, C, D>> {
+        self.indices.get(&key).map(|i| HexTileRef::from(&self.tiles[*i]))
+    }
+
+    fn mut_borrow_tile_by_key(&mut self, key: CoordKey<C::Int>) -> Option<HexTileMut<'_, C, D>> {
+        match self.indices.get(&key) {
+            Some(i) => Some(HexTileMut::from(&mut self.tiles[*i])),
+            None => None
+        }
+    }
+
+    fn mut_borrow_tiles_by_keys<const N: usize>(&mut self, keys: [CoordKey<C::Int>; N]) -> [Option<HexTileMut<'_, C, D>>; N] {
+        let indices = keys.map(|key| self.indices.get(&key).cloned());
+        disjoint_mut(&mut self.tiles, indices).map(|borrow| borrow.map(|(_, tile)| HexTileMut::from(tile)))
+    }
+
+    fn insert_tile(&mut self, tile: HexTile<C, D>) -> bool {
+        match self.indices.get(&tile.get_key()) {
+            Some(i) => self.tiles[*i] = tile,
+            None => {
+                self.indices.insert(tile.get_key(), self.tiles.len());
+                self.tiles.push(tile);
+            }
+        }
+        true
+    }
+
+    fn mutate_all_data(&mut self, f: &Fn(&mut D)) {
+        for tile in self.tiles.iter_mut() {
+            tile.mutate_data(f);
+        }
+    }
+
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.tiles.iter().map(HexTileRef::from)
+    }
+
+    fn iter_mut<'a>(&'a mut self) -> Self::IterMut<'a> {
+        self.tiles.iter_mut().map(HexTileMut::from)
+    }
+}
+
+impl<'a, C: CoordType, D> IntoIterator for &'a HexGridHashMapWrapper<C, D> {
+    type Item = HexTileRef<'a, C, D>;
+    type IntoIter = HashMapIter<'a, C, D>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Tiles are stored as a plain sequence, the keys can be rebuilt from the tiles themselves
+
+#[cfg(feature = "serde")]
+impl<C: CoordType + Serialize, D: Serialize> Serialize for HexGridHashMapWrapper<C, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.tiles.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, C: CoordType + Deserialize<'de>, D: Deserialize<'de>> Deserialize<'de> for HexGridHashMapWrapper<C, D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let tiles: Vec<HexTile<C, D>> = Vec::deserialize(deserializer)?;
+        let mut wrapper = Self::new();
+        for tile in tiles {
+            wrapper.insert_tile(tile);
+        }
+        Ok(wrapper)
+    }
+}
+
+/*
+    Define outer grid construct
+*/
+
+// A mutably borrowed tile alongside its mutably borrowed neighbours
+pub type TileWithNeighbours<'a, C, D> = (HexTileMut<'a, C, D>, Vec<HexTileMut<'a, C, D>>);
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "W: Serialize, C::Int: Serialize", deserialize = "W: Deserialize<'de>, C::Int: Deserialize<'de>")))]
+pub struct HexGrid<C: CoordType, D, W: HexGridDataWrapper<C, D>> {
+    pub wrapper: W,
+    hexshape: HexShape,
+    #[cfg_attr(feature = "serde", serde(default))]
+    topology: Topology<C::Int>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _c: PhantomData<C>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _d: PhantomData<D>
+}
+
+impl<C: CoordType, D, W: HexGridDataWrapper<C, D>> HexGrid<C, D, W> {
+    pub fn new(hexshape: HexShape, init: Option<&Fn() -> W>) -> Self {
+        match init {
+            Some(f) => Self {
+                wrapper: f(),
+                hexshape,
+                topology: Topology::Plane,
+                _c: PhantomData,
+                _d: PhantomData
+            },
+            None => Self {
+                wrapper: W::new(),
+                hexshape,
+                topology: Topology::Plane,
+                _c: PhantomData,
+                _d: PhantomData
+            }
+        }
+    }
+
+    // Coordinates the wrapper can't store are returned as the error (see `HexGridDataWrapper::initialize`)
+    pub fn initialize<I>(&mut self, init: I, new_data: &Fn() -> D) -> Result<(), Vec<C>>
+            where I: Iterator<Item=C> {
+        self.wrapper.initialize(init, new_data)
+    }
+
+    pub fn borrow_tile_by_key(&self, key: CoordKey<C::Int>) -> Option<HexTileRef<'_, C, D>> {
+        self.wrapper.borrow_tile_by_key(key)
+    }
+
+    pub fn mut_borrow_tile_by_key(&mut self, key: CoordKey<C::Int>) -> Option<HexTileMut<'_, C, D>> {
+        self.wrapper.mut_borrow_tile_by_key(key)
+    }
+
+    /*
+        Topology (see `topology::Topology`), tiles of wrapping maps should all be on the map
+    */
+
+    pub fn topology(&self) -> Topology<C::Int> {
+        self.topology
+    }
+
+    // Panics if the topology can't wrap maps of this grid's orientation
+    pub fn set_topology(&mut self, topology: Topology<C::Int>) {
+        assert!(topology.is_valid_for(self.hexshape.orient()), "{:?} can't wrap {:?} maps", topology, self.hexshape.orient());
+        self.topology = topology;
+    }
+
+    pub fn distance(&self, a: &C, b: &C) -> C::Int {
+        self.topology.distance(a, b)
+    }
+
+    pub fn line(&self, a: &C, b: &C) -> Vec<C> {
+        self.topology.line(a, b)
+    }
+
+    /*
+        Neighbouring tiles (only tiles which exist in the grid are returned)
+    */
+
+    // Keys of the (wrapped) neighbours of a coordinate
+    fn neighbour_keys(&self, coord: &C) -> [CoordKey<C::Int>; 6] {
+        self.topology.neighbours(coord).map(|n| n.get_key())
+    }
+
+    pub fn neighbours_of<'a>(&'a self, coord: &C) -> impl Iterator<Item=HexTileRef<'a, C, D>> + 'a {
+        let keys = self.neighbour_keys(coord);
+        // Wrapping can make neighbours coincide on small maps, each tile is only given once
+        let wraps = self.topology != Topology::Plane;
+        (0..6).filter(move |i| !wraps || !keys[..*i].contains(&keys[*i]))
+            .filter_map(move |i| self.borrow_tile_by_key(keys[i]))
+    }
+
+    pub fn neighbours_of_key<'a>(&'a self, key: CoordKey<C::Int>) -> impl Iterator<Item=HexTileRef<'a, C, D>> + 'a {
+        self.neighbours_of(&C::from_key(key, self.hexshape.orient()))
+    }
+
+    pub fn mut_neighbours_of(&mut self, coord: &C) -> Vec<HexTileMut<'_, C, D>> {
+        let keys = self.neighbour_keys(coord);
+        IntoIterator::into_iter(self.wrapper.mut_borrow_tiles_by_keys(keys)).flatten().collect()
+    }
+
+    pub fn mut_neighbours_of_key(&mut self, key: CoordKey<C::Int>) -> Vec<HexTileMut<'_, C, D>> {
+        let coord = C::from_key(key, self.hexshape.orient());
+        self.mut_neighbours_of(&coord)
+    }
+
+    // Mutably borrow a tile alongside its existing neighbours, None if the tile itself doesn't exist
+    pub fn mut_borrow_with_neighbours(&mut self, coord: &C) -> Option<TileWithNeighbours<'_, C, D>> {
+        // Neighbours which wrap round onto the tile itself (or each other) are only borrowed once
+        let center = self.topology.wrap(coord).get_key();
+        let [a, b, c, d, e, f] = self.neighbour_keys(coord);
+        let [center, a, b, c, d, e, f] = self.wrapper.mut_borrow_tiles_by_keys([center, a, b, c, d, e, f]);
+        center.map(|center| (center, IntoIterator::into_iter([a, b, c, d, e, f]).flatten().collect()))
+    }
+
+    pub fn mut_borrow_with_neighbours_by_key(&mut self, key: CoordKey<C::Int>) -> Option<TileWithNeighbours<'_, C, D>> {
+        let coord = C::from_key(key, self.hexshape.orient());
+        self.mut_borrow_with_neighbours(&coord)
+    }
+
+    pub fn borrow_data_by_key(&self, key: CoordKey<C::Int>) -> Option<&D> {
+        match self.borrow_tile_by_key(key) {
+            Some(tile) => Some(tile.borrow_data()),
+            None => None
+        }
+    }
+
+    pub fn mut_borrow_data_by_key(&mut self, key: CoordKey<C::Int>) -> Option<&mut D> {
+        match self.mut_borrow_tile_by_key(key) {
+            Some(tile) => Some(tile.into_data()),
+            None => None
+        }
+    }
+
+    pub fn mutate_all_data(&mut self, f: &Fn(&mut D)) {
+        self.wrapper.mutate_all_data(f)
+    }
+
+    pub fn mutate_all_tiles<F>(&mut self, mut f: F) where F: FnMut(&C, &mut D) {
+        for mut tile in self.wrapper.iter_mut() {
+            let (coord, data) = tile.mut_borrow_coord_and_data();
+            f(coord, data);
+        }
+    }
+
+    // Build a new grid with the same shape, topology and kind of wrapper, and a different data type
+    pub fn map_data<E>(&self, f: impl Fn(&C, &D) -> E) -> HexGrid<C, E, W::Mapped<E>> {
+        self.filter_map(|coord, data| Some(f(coord, data)))
+    }
+
+    // As `map_data`, but tiles for which `f` returns `None` are left out of the new grid
+    pub fn filter_map<E>(&self, f: impl Fn(&C, &D) -> Option<E>) -> HexGrid<C, E, W::Mapped<E>> {
+        let mut grid = HexGrid {
+            wrapper: self.wrapper.empty_like(),
+            hexshape: self.hexshape,
+            topology: self.topology,
+            _c: PhantomData,
+            _d: PhantomData
+        };
+        for tile in self.iter() {
+            let coord = tile.borrow_coord();
+            if let Some(data) = f(coord, tile.borrow_data()) {
+                let stored = grid.wrapper.insert_tile(HexTile::new(*coord, data));
+                assert!(stored, "HexGridDataWrapper::empty_like returned a wrapper which can't store every tile");
+            }
+        }
+        grid
+    }
+
+    // Every tile that can be reached from `start` for at most `budget`, with the cost of entering
+    // each tile given by `cost` (None if it can't be entered). Paths can be rebuilt from the result
+    pub fn reachable<F>(&self, start: &C, budget: u32, cost: F) -> SearchResult<C> where F: Fn(&D) -> Option<u32> {
+        pathfinding::search(self, start, None, Some(budget), |_, to| cost(to), |_| 0)
+    }
+
+    // Keys of every tile within `radius` of `origin` that can be seen from it, `blocks` says
+    // whether a tile's data stops sight passing through it
+    pub fn visible_from<F>(&self, origin: &C, radius: C::Int, mode: FovMode, blocks: F) -> HashSet<CoordKey<C::Int>>
+            where F: Fn(&D) -> bool {
+        visibility::visible_from(self, origin, radius, mode, blocks)
+    }
+
+    // Keys of every tile connected to `seed` through tiles whose data satisfies `predicate`
+    pub fn flood_fill<P>(&self, seed: &C, predicate: P) -> HashSet<CoordKey<C::Int>> where P: Fn(&D) -> bool {
+        regions::flood_fill(self, seed, predicate)
+    }
+
+    // Label the separate regions formed by tiles whose data satisfies `predicate`
+    pub fn connected_components<P>(&self, predicate: P) -> Components<C::Int> where P: Fn(&D) -> bool {
+        regions::connected_components(self, predicate)
+    }
+
+    pub fn iter<'a>(&'a self) -> W::Iter<'a> {
+        self.wrapper.iter()
+    }
+
+    pub fn iter_mut<'a>(&'a mut self) -> W::IterMut<'a> {
+        self.wrapper.iter_mut()
+    }
+
+    pub fn keys<'a>(&'a self) -> impl Iterator<Item=CoordKey<C::Int>> + 'a {
+        self.iter().map(|tile| tile.get_key())
+    }
+
+    pub fn coords<'a>(&'a self) -> impl Iterator<Item=C> + 'a {
+        self.iter().map(|tile| *tile.borrow_coord())
+    }
+
+    pub fn data<'a>(&'a self) -> impl Iterator<Item=&'a D> + 'a {
+        self.iter().map(|tile| tile.borrow_data())
+    }
+
+    pub fn data_mut<'a>(&'a mut self) -> impl Iterator<Item=&'a mut D> + 'a {
+        self.iter_mut().map(|tile| tile.into_data())
+    }
+
+    // Takes the `Layout` the grid is drawn with, or its `HexShape` if the grid isn't scaled or moved
+    pub fn mut_borrow_data_for_pixel<F: CoordFloat, L: Into<Layout<F>>>(&mut self, (x, y): (F, F), layout: L) -> Option<&mut D> {
+        let layout = layout.into();
+        layout.assert_orientation(self.hexshape.orient());
+        let key = self.topology.wrap(&C::from_pixel((x, y), layout)).get_key();
+        self.mut_borrow_data_by_key(key)
+    }
+}
+
+/*
+    Allow HexGrid to cast into an iterator of all its tiles
+*/
+
+impl<'a, C: CoordType, D, W: HexGridDataWrapper<C, D>> IntoIterator for &'a HexGrid<C, D, W> {
+    type Item = HexTileRef<'a, C, D>;
+    type IntoIter = W::Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, C: CoordType, D, W: HexGridDataWrapper<C, D>> IntoIterator for &'a mut HexGrid<C, D, W> {
+    type Item = HexTileMut<'a, C, D>;
+    type IntoIter = W::IterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::coords::CubeCoords;
+    use super::initializers::{HexagonalCenteredGridIterator, SquareFlatOffsetOddGridIterator};
+
+    #[cfg(feature = "serde")]
+    extern crate serde_json;
+
+    type CubeGrid = HexGrid<CubeCoords, u8, HexGridHashMapWrapper<CubeCoords, u8>>;
+
+    fn make_grid(range: i32, orient: Orientation) -> CubeGrid {
+        let mut grid = CubeGrid::new(HexShape::new(10.0, orient), None);
+        grid.initialize(HexagonalCenteredGridIterator::new(range, orient), &|| 7).unwrap();
+        grid
+    }
+
+    #[test]
+    fn test_initialize_and_borrow() {
+        let mut grid = make_grid(2, Orientation::Flat);
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((1, -2, 1))), Some(&7));
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((3, -3, 0))), None);
+
+        *grid.mut_borrow_data_by_key(CoordKey::from((0, 0, 0))).unwrap() = 1;
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((0, 0, 0))), Some(&1));
+    }
+
+    #[test]
+    fn test_rectangle_wrapper() {
+        let orient = Orientation::Pointy;
+        let mut grid: HexGrid<OffsetOddCoords, u8, HexGridRectangleWrapper<OffsetOddCoords, u8>> =
+            HexGrid::new(HexShape::new(10.0, orient), Some(&|| HexGridRectangleWrapper::with_size(4, 3, orient)));
+        let origin = OffsetOddCoords::new((0, 0), orient);
+        let outside = grid.initialize(SquareFlatOffsetOddGridIterator::new(origin, 5, 3), &|| 1).unwrap_err();
+        assert_eq!(outside.iter().map(|c| c.get()).collect::<Vec<_>>(), vec![(4, 0), (4, 1), (4, 2)]);
+
+        assert_eq!(grid.wrapper.into_iter().count(), 12);
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((3, 2))), Some(&1));
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((4, 2))), None);
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((-1, 0))), None);
+        assert_eq!(grid.borrow_tile_by_key(CoordKey::from((2, 1))).unwrap().borrow_coord().get(), (2, 1));
+
+        grid.mutate_all_data(&|d: &mut u8| *d += 1);
+        assert!(grid.wrapper.into_iter().all(|t| *t.borrow_data() == 2));
+    }
+
+    #[test]
+    fn test_hexagon_wrapper() {
+        let center = CubeCoords::new((0, 0, 0), Orientation::Flat);
+        let mut grid: HexGrid<CubeCoords, u8, HexGridHexagonWrapper<CubeCoords, u8>> =
+            HexGrid::new(HexShape::new(10.0, Orientation::Flat), Some(&|| HexGridHexagonWrapper::centered_at(&center, 3)));
+        assert_eq!(grid.initialize(HexagonalCenteredGridIterator::new(4, Orientation::Flat), &|| 1).unwrap_err().len(), 24);
+
+        assert_eq!(grid.wrapper.into_iter().count(), 37);
+        for tile in grid.wrapper.into_iter() {
+            assert_eq!(grid.borrow_tile_by_key(tile.get_key()).unwrap().borrow_coord(), tile.borrow_coord());
+        }
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((3, -3, 0))), Some(&1));
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((4, -4, 0))), None);
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((2, 2, -4))), None);
+    }
+
+    #[test]
+    fn test_hexagon_wrapper_off_center() {
+        let center = CubeCoords::new((5, -2, -3), Orientation::Pointy);
+        let wrapper: HexGridHexagonWrapper<CubeCoords, u8> = HexGridHexagonWrapper::centered_at(&center, 1);
+        let mut grid: HexGrid<CubeCoords, u8, _> = HexGrid::new(HexShape::new(10.0, Orientation::Pointy), None);
+        grid.wrapper = wrapper;
+        grid.initialize(::std::iter::once(center).chain(center.neighbours().iter().cloned()), &|| 3).unwrap();
+        assert_eq!(grid.wrapper.into_iter().count(), 7);
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((6, -3, -3))), Some(&3));
+
+        // Coordinates are rebuilt from where the data is stored
+        let mut coords: Vec<CubeCoords> = grid.coords().collect();
+        let mut expected = center.neighbours().to_vec();
+        expected.push(center);
+        coords.sort();
+        expected.sort();
+        assert_eq!(coords, expected);
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((0, 0, 0))), None);
+    }
+
+    #[test]
+    fn test_wide_grid() {
+        // Grids work in the integer type of their coordinates, far past the range of i32
+        let far = 5_000_000_000_000i64;
+        let center = CubeCoords::new((far, -far, 0), Orientation::Flat);
+        let mut grid: HexGrid<CubeCoords<i64>, u8, HexGridHexagonWrapper<CubeCoords<i64>, u8>> =
+            HexGrid::new(HexShape::new(10.0, Orientation::Flat), None);
+        grid.wrapper = HexGridHexagonWrapper::centered_at(&center, 2);
+        grid.initialize(coords::alg::spiral(&center, 3), &|| 1).unwrap_err();
+        assert_eq!(grid.iter().count(), 19);
+        assert_eq!(grid.neighbours_of(&center).count(), 6);
+        assert_eq!(grid.neighbours_of(&center.neighbour(0).neighbour(0)).count(), 3);
+
+        let goal = CubeCoords::new((far - 2, -far + 1, 1), Orientation::Flat);
+        assert_eq!(grid.distance(&center, &goal), 2);
+        let path = pathfinding::astar(&grid, &center, &goal, |d| Some(*d as u32)).unwrap();
+        assert_eq!(path.len(), 3);
+    }
+
+    #[test]
+    fn test_iteration() {
+        let mut grid = make_grid(2, Orientation::Flat);
+        assert_eq!(grid.iter().count(), 19);
+        assert_eq!(grid.keys().count(), 19);
+        assert!(grid.coords().all(|c| c.distance(&CubeCoords::new((0, 0, 0), Orientation::Flat)) <= 2));
+
+        // Tiles can be mutated using their own coordinate
+        for mut tile in grid.iter_mut() {
+            let (x, _, _) = tile.borrow_coord().get();
+            tile.set_data((x + 2) as u8);
+        }
+        for tile in &grid {
+            assert_eq!(*tile.borrow_data(), (tile.borrow_coord().get().0 + 2) as u8);
+        }
+
+        for d in grid.data_mut() {
+            *d *= 2;
+        }
+        assert_eq!(grid.data().map(|d| *d as i32).sum::<i32>(), 19 * 4);
+    }
+
+    #[test]
+    fn test_iteration_dense_wrappers() {
+        let orient = Orientation::Flat;
+        let mut grid: HexGrid<OffsetOddCoords, u8, HexGridRectangleWrapper<OffsetOddCoords, u8>> =
+            HexGrid::new(HexShape::new(10.0, orient), Some(&|| HexGridRectangleWrapper::with_size(3, 3, orient)));
+        grid.initialize(SquareFlatOffsetOddGridIterator::new(OffsetOddCoords::new((0, 0), orient), 3, 3), &|| 0).unwrap();
+        for mut tile in &mut grid {
+            let (x, y) = tile.borrow_coord().get();
+            tile.set_data((x + y * 3) as u8);
+        }
+        let mut keys: Vec<CoordKey> = grid.keys().collect();
+        keys.sort();
+        assert_eq!(keys.len(), 9);
+        assert_eq!(grid.borrow_data_by_key(CoordKey::from((2, 1))), Some(&5));
+
+        let mut grid: HexGrid<CubeCoords, u8, HexGridHexagonWrapper<CubeCoords, u8>> =
+            HexGrid::new(HexShape::new(10.0, orient), Some(&|| HexGridHexagonWrapper::with_radius(2, orient)));
+        grid.initialize(HexagonalCenteredGridIterator::new(2, orient), &|| 1).unwrap();
+        for d in grid.data_mut() {
+            *d += 1;
+        }
+        assert_eq!(grid.data().filter(|d| **d == 2).count(), 19);
+    }
+
+    #[test]
+    fn test_mutate_all_tiles() {
+        let mut grid = make_grid(2, Orientation::Flat);
+        let origin = CubeCoords::new((0, 0, 0), Orientation::Flat);
+        grid.mutate_all_tiles(|coord, data| *data = coord.distance(&origin) as u8);
+        for tile in grid.iter() {
+            assert_eq!(*tile.borrow_data() as i32, tile.borrow_coord().distance(&origin));
+        }
+    }
+
+    #[test]
+    fn test_map_data() {
+        let mut grid = make_grid(2, Orientation::Pointy);
+        grid.mutate_all_tiles(|coord, data| *data = (coord.get().0 + 2) as u8);
+
+        let costs = grid.map_data::<f32>(|_, data| *data as f32 * 0.5);
+        assert_eq!(costs.iter().count(), 19);
+        assert_eq!(costs.borrow_data_by_key(CoordKey::from((2, -2, 0))), Some(&2.0));
+        assert_eq!(costs.hexshape, grid.hexshape);
+    }
+
+    #[test]
+    fn test_map_data_dense() {
+        let orient = Orientation::Pointy;
+        let mut grid: HexGrid<CubeCoords, u8, HexGridHexagonWrapper<CubeCoords, u8>> =
+            HexGrid::new(HexShape::new(10.0, orient), Some(&|| HexGridHexagonWrapper::with_radius(2, orient)));
+        grid.initialize(HexagonalCenteredGridIterator::new(2, orient), &|| 0).unwrap();
+        grid.mutate_all_tiles(|coord, data| *data = (coord.get().0 + 2) as u8);
+
+        // The mapped grid has the same bounds, so keeps every tile
+        let high: HexGrid<CubeCoords, bool, HexGridHexagonWrapper<CubeCoords, bool>> = grid.map_data(|_, data| *data > 2);
+        assert_eq!(high.wrapper.radius(), 2);
+        assert_eq!(high.iter().count(), 19);
+        assert_eq!(high.data().filter(|d| **d).count(), 7);
+
+        let rows = grid.filter_map(|coord, data| if coord.get().2 == 0 { Some(*data as i32) } else { None });
+        assert_eq!(rows.data().sum::<i32>(), 5 * 2);
+    }
+
+    #[test]
+    fn test_filter_map() {
+        let grid = make_grid(2, Orientation::Flat);
+        let origin = CubeCoords::new((0, 0, 0), Orientation::Flat);
+        let ring: HexGrid<CubeCoords, i32, HexGridHashMapWrapper<CubeCoords, i32>> = grid.filter_map(|coord, data| {
+            match coord.distance(&origin) {
+                2 => Some(*data as i32),
+                _ => None
+            }
+        });
+        assert_eq!(ring.iter().count(), 12);
+        assert!(ring.coords().all(|c| c.distance(&origin) == 2));
+        assert!(ring.data().all(|d| *d == 7));
+    }
+
+    #[test]
+    fn test_neighbours_of() {
+        let grid = make_grid(2, Orientation::Flat);
+        let center = CubeCoords::new((0, 0, 0), Orientation::Flat);
+        assert_eq!(grid.neighbours_of(&center).count(), 6);
+
+        // Only 3 of the neighbours of a corner tile are within the map
+        let corner = CubeCoords::new((2, -2, 0), Orientation::Flat);
+        assert_eq!(grid.neighbours_of(&corner).count(), 3);
+        assert_eq!(grid.neighbours_of_key(corner.get_key()).count(), 3);
+        assert!(grid.neighbours_of(&corner).all(|t| t.borrow_coord().distance(&corner) == 1));
+
+        let outside = CubeCoords::new((4, -4, 0), Orientation::Flat);
+        assert_eq!(grid.neighbours_of(&outside).count(), 0);
+    }
+
+    #[test]
+    fn test_mut_neighbours_of() {
+        let mut grid = make_grid(2, Orientation::Pointy);
+        let edge = CubeCoords::new((2, -1, -1), Orientation::Pointy);
+        for mut tile in grid.mut_neighbours_of(&edge) {
+            tile.set_data(0);
+        }
+        assert_eq!(grid.data().filter(|d| **d == 0).count(), 4);
+        assert_eq!(grid.mut_neighbours_of_key(edge.get_key()).len(), 4);
+    }
+
+    #[test]
+    fn test_mut_borrow_with_neighbours() {
+        let mut grid = make_grid(1, Orientation::Flat);
+        let center = CubeCoords::new((0, 0, 0), Orientation::Flat);
+        {
+            let (mut tile, neighbours) = grid.mut_borrow_with_neighbours(&center).unwrap();
+            assert_eq!(neighbours.len(), 6);
+            for mut n in neighbours {
+                *tile.mut_borrow_data() += *n.borrow_data();
+                n.set_data(0);
+            }
+        }
+        assert_eq!(grid.borrow_data_by_key(center.get_key()), Some(&49));
+        assert_eq!(grid.data().filter(|d| **d == 0).count(), 6);
+        assert!(grid.mut_borrow_with_neighbours(&CubeCoords::new((3, -3, 0), Orientation::Flat)).is_none());
+    }
+
+    #[test]
+    fn test_mut_borrow_with_neighbours_dense() {
+        let orient = Orientation::Flat;
+        let mut grid: HexGrid<OffsetOddCoords, u8, HexGridRectangleWrapper<OffsetOddCoords, u8>> =
+            HexGrid::new(HexShape::new(10.0, orient), Some(&|| HexGridRectangleWrapper::with_size(3, 3, orient)));
+        grid.initialize(SquareFlatOffsetOddGridIterator::new(OffsetOddCoords::new((0, 0), orient), 3, 3), &|| 1).unwrap();
+        let (mut tile, neighbours) = grid.mut_borrow_with_neighbours_by_key(CoordKey::from((0, 0))).unwrap();
+        assert_eq!(neighbours.len(), 2);
+        for mut n in neighbours {
+            n.set_data(5);
+        }
+        tile.set_data(9);
+        assert_eq!(grid.data().map(|d| *d as i32).sum::<i32>(), 9 + 5 * 2 + 6);
+    }
+
+    #[test]
+    fn test_duplicate_mut_borrow() {
+        let mut wrapper: HexGridHexagonWrapper<CubeCoords, u8> = HexGridHexagonWrapper::with_radius(1, Orientation::Flat);
+        wrapper.initialize(HexagonalCenteredGridIterator::new(1, Orientation::Flat), &|| 0).unwrap();
+        let (key, other, outside) = (CoordKey::from((0, 0, 0)), CoordKey::from((1, -1, 0)), CoordKey::from((2, -2, 0)));
+        let [a, b, c, d] = wrapper.mut_borrow_tiles_by_keys([key, outside, other, key]);
+        assert_eq!(a.unwrap().borrow_coord().get(), (0, 0, 0));
+        assert!(b.is_none());
+        assert_eq!(c.unwrap().borrow_coord().get(), (1, -1, 0));
+        assert!(d.is_none());
+
+        let mut grid = make_grid(1, Orientation::Flat);
+        let [a, b, c] = grid.wrapper.mut_borrow_tiles_by_keys([other, key, other]);
+        assert_eq!((a.is_some(), b.is_some(), c.is_some()), (true, true, false));
+    }
+
+    #[test]
+    fn test_reachable() {
+        let orient = Orientation::Flat;
+        let mut grid = make_grid(4, orient);
+        let start = CubeCoords::new((0, 0, 0), orient);
+        let step = |d: &u8| if *d == 0 { None } else { Some(*d as u32) };
+
+        grid.mutate_all_data(&|d| *d = 1);
+        let open = grid.reachable(&start, 2, step);
+        assert_eq!(open.costs.len(), 19);
+        for key in grid.keys() {
+            let coord = CubeCoords::from_key(key, orient);
+            assert_eq!(open.cost_to(&coord), if start.distance(&coord) <= 2 { Some(start.distance(&coord) as u32) } else { None });
+        }
+
+        // A wall to the north east and a swamp to the south
+        let wall = CubeCoords::new((1, 0, -1), orient);
+        let swamp = CubeCoords::new((0, -1, 1), orient);
+        grid.mutate_all_tiles(|c, d| if *c == wall { *d = 0 } else if *c == swamp { *d = 3 });
+        let blocked = grid.reachable(&start, 2, step);
+        assert!(!blocked.contains(&wall));
+        assert!(!blocked.contains(&swamp));
+        assert!(!blocked.contains(&CubeCoords::new((2, 0, -2), orient)));
+        assert!(blocked.contains(&CubeCoords::new((2, -1, -1), orient)));
+
+        let far = CubeCoords::new((1, -2, 1), orient);
+        let path = blocked.path_to(&far).unwrap();
+        assert_eq!(path.len(), 3);
+        assert_eq!(path[0], start);
+        assert!(!path.contains(&swamp));
+        assert_eq!(blocked.path_to(&swamp), None);
+
+        assert_eq!(grid.reachable(&start, 0, step).costs.len(), 1);
+        assert!(grid.reachable(&CubeCoords::new((9, -9, 0), orient), 5, step).costs.is_empty());
+    }
+
+    #[test]
+    fn test_borrow_data_for_pixel() {
+        let mut grid = make_grid(2, Orientation::Flat);
+        let hex = CubeCoords::new((1, -2, 1), Orientation::Flat);
+        let layout = Layout { flip_y: true, ..Layout::new(Orientation::Flat, (20.0, 8.0), (320.0, 240.0)) };
+        *grid.mut_borrow_data_for_pixel(hex.to_pixel(layout).get(), layout).unwrap() = 1;
+        assert_eq!(grid.borrow_data_by_key(hex.get_key()), Some(&1));
+        assert!(grid.mut_borrow_data_for_pixel((0.0, 0.0), layout).is_none());
+
+        let shape = HexShape::new(10.0, Orientation::Flat);
+        *grid.mut_borrow_data_for_pixel(hex.to_pixel(shape).get(), shape).unwrap() = 2;
+        assert_eq!(grid.borrow_data_by_key(hex.get_key()), Some(&2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_borrow_data_for_pixel_orientation_mismatch() {
+        let mut grid = make_grid(2, Orientation::Flat);
+        let _ = grid.mut_borrow_data_for_pixel((0.0, 0.0), HexShape::new(10.0, Orientation::Pointy));
+    }
+
+    #[test]
+    fn test_wrapping_neighbours() {
+        let orient = Orientation::Pointy;
+        let mut grid: HexGrid<OffsetOddCoords, u8, HexGridRectangleWrapper<OffsetOddCoords, u8>> =
+            HexGrid::new(HexShape::new(10.0, orient), Some(&|| HexGridRectangleWrapper::with_size(4, 3, orient)));
+        grid.initialize(SquareFlatOffsetOddGridIterator::new(OffsetOddCoords::new((0, 0), orient), 4, 3), &|| 0).unwrap();
+        let edge = OffsetOddCoords::new((0, 1), orient);
+        assert_eq!(grid.neighbours_of(&edge).count(), 5);
+
+        grid.set_topology(Topology::Cylinder { width: 4 });
+        let neighbours: Vec<(i32, i32)> = grid.neighbours_of(&edge).map(|t| t.borrow_coord().get()).collect();
+        assert_eq!(neighbours.len(), 6);
+        assert!(neighbours.contains(&(3, 1)));
+        assert_eq!(grid.distance(&edge, &OffsetOddCoords::new((3, 1), orient)), 1);
+        assert_eq!(grid.line(&edge, &OffsetOddCoords::new((3, 1), orient)).len(), 2);
+
+        // Topology carries over to mapped grids
+        let mapped = grid.map_data(|_, d| *d);
+        assert_eq!(mapped.topology(), Topology::Cylinder { width: 4 });
+        assert_eq!(mapped.wrapper.size(), (4, 3));
+        assert_eq!(mapped.iter().count(), 12);
+    }
+
+    #[test]
+    fn test_wrapping_coinciding_neighbours() {
+        // On a map two hexes wide the hexes either side of a tile are the same tile
+        let orient = Orientation::Pointy;
+        let mut grid: HexGrid<OffsetOddCoords, u8, HexGridHashMapWrapper<OffsetOddCoords, u8>> =
+            HexGrid::new(HexShape::new(10.0, orient), None);
+        grid.initialize(SquareFlatOffsetOddGridIterator::new(OffsetOddCoords::new((0, 0), orient), 2, 2), &|| 0).unwrap();
+        grid.set_topology(Topology::Torus { width: 2, height: 2 });
+        let origin = OffsetOddCoords::new((0, 0), orient);
+        assert_eq!(grid.neighbours_of(&origin).count(), 3);
+        assert_eq!(grid.mut_neighbours_of(&origin).len(), 3);
+
+        grid.set_topology(Topology::Torus { width: 1, height: 2 });
+        let (mut tile, neighbours) = grid.mut_borrow_with_neighbours(&origin).unwrap();
+        tile.set_data(1);
+        assert_eq!(neighbours.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_topology() {
+        let mut grid = make_grid(2, Orientation::Flat);
+        grid.set_topology(Topology::Cylinder { width: 5 });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut grid = make_grid(2, Orientation::Pointy);
+        grid.mutate_all_data(&|d: &mut u8| *d += 1);
+
+        let json = serde_json::to_string(&grid).unwrap();
+        let loaded: CubeGrid = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.hexshape, HexShape::PointyTop(10.0));
+        assert_eq!(loaded.wrapper.tiles.len(), 19);
+        for tile in grid.iter() {
+            let other = loaded.borrow_tile_by_key(tile.get_key()).unwrap();
+            assert_eq!(other.borrow_coord(), tile.borrow_coord());
+            assert_eq!(other.borrow_data(), tile.borrow_data());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_coords() {
+        use super::coords::OffsetOddCoords;
+        let coord = OffsetOddCoords::new((3, -2), Orientation::Flat);
+        let json = serde_json::to_string(&coord).unwrap();
+        assert_eq!(serde_json::from_str::<OffsetOddCoords>(&json).unwrap(), coord);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_invalid_cube() {
+        let cube = CubeCoords::new((1, -3, 2), Orientation::Flat);
+        let json = serde_json::to_string(&cube).unwrap();
+        assert_eq!(serde_json::from_str::<CubeCoords>(&json).unwrap(), cube);
+        assert!(serde_json::from_str::<CubeCoords>(r#"{"x":1,"y":1,"z":1,"orientation":"Flat"}"#).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::convert::TryFrom;
+
+use super::{HexGrid, HexGridDataWrapper};
+use super::coords::{CoordType, CoordKey};
+use super::coords::num::CoordInt;
+
+/*
+    Shortest path searches over the tiles of a grid
+
+    Costs are given by a closure returning the cost of moving onto a tile, or `None` if the tile
+    can't be entered. The `_edge` variants are given both the tile being left and the tile being
+    entered, for costs that depend on the edge crossed (e.g. climbing vs descending).
+
+    The A* heuristic is the hex distance to the goal (going round wrapping maps where that's
+    shorter), so costs should be at least 1 per step for the paths it finds to be optimal. Paths
+    include both the start and the goal.
+
+    `HexGrid::reachable` runs the same search without a goal, stopping at a movement budget.
+*/
+
+// Cost to reach every visited tile, and the tile each one was reached from
+pub struct SearchResult<C: CoordType> {
+    pub start: C,
+    pub costs: HashMap<CoordKey<C::Int>, u32>,
+    pub came_from: HashMap<CoordKey<C::Int>, C>
+}
+
+impl<C: CoordType> SearchResult<C> {
+    pub fn contains(&self, coord: &C) -> bool {
+        self.costs.contains_key(&coord.get_key())
+    }
+
+    pub fn cost_to(&self, coord: &C) -> Option<u32> {
+        self.costs.get(&coord.get_key()).cloned()
+    }
+
+    // Rebuild the path from the start to `goal`, None if `goal` wasn't reached
+    pub fn path_to(&self, goal: &C) -> Option<Vec<C>> {
+        if !self.contains(goal) {
+            return None
+        }
+        let mut path = vec![*goal];
+        let mut current = *goal;
+        while current.get_key() != self.start.get_key() {
+            current = self.came_from[&current.get_key()];
+            path.push(current);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+pub(crate) fn search<C: CoordType, D, W, F, H>(grid: &HexGrid<C, D, W>, start: &C, goal: Option<&C>,
+        budget: Option<u32>, cost: F, heuristic: H) -> SearchResult<C>
+        where W: HexGridDataWrapper<C, D>, F: Fn(&D, &D) -> Option<u32>, H: Fn(&C) -> u32 {
+    // Coordinates off the edge of a wrapping map are searched from (or for) their copy on the map
+    let start = &grid.topology().wrap(start);
+    let goal = goal.map(|g| grid.topology().wrap(g).get_key());
+    let mut result = SearchResult { start: *start, costs: HashMap::new(), came_from: HashMap::new() };
+    if grid.borrow_tile_by_key(start.get_key()).is_none() {
+        return result
+    }
+
+    let mut frontier = BinaryHeap::new();
+    frontier.push(Reverse((heuristic(start), 0, start.get_key())));
+    result.costs.insert(start.get_key(), 0);
+
+    while let Some(Reverse((_, current_cost, key))) = frontier.pop() {
+        if Some(key) == goal {
+            break
+        }
+        // Skip entries superseded by a cheaper route found after they were queued
+        if result.costs.get(&key).map_or(false, |c| *c < current_cost) {
+            continue
+        }
+        let current = match grid.borrow_tile_by_key(key) {
+            Some(tile) => tile,
+            None => continue
+        };
+        for next in grid.neighbours_of(current.borrow_coord()) {
+            let step = match cost(current.borrow_data(), next.borrow_data()) {
+                Some(step) => step,
+                None => continue
+            };
+            // Routes whose cost doesn't fit in a u32 are never worth taking
+            let next_cost = match current_cost.checked_add(step) {
+                Some(next_cost) => next_cost,
+                None => continue
+            };
+            if budget.map_or(false, |b| next_cost > b) {
+                continue
+            }
+            let next_key = next.get_key();
+            if result.costs.get(&next_key).map_or(true, |c| next_cost < *c) {
+                result.costs.insert(next_key, next_cost);
+                result.came_from.insert(next_key, *current.borrow_coord());
+                frontier.push(Reverse((next_cost.saturating_add(heuristic(next.borrow_coord())), next_cost, next_key)));
+            }
+        }
+    }
+    result
+}
+
+// A*
+
+pub fn astar<C: CoordType, D, W, F>(grid: &HexGrid<C, D, W>, start: &C, goal: &C, cost: F) -> Option<Vec<C>>
+        where W: HexGridDataWrapper<C, D>, F: Fn(&D) -> Option<u32> {
+    astar_edge(grid, start, goal, |_, to| cost(to))
+}
+
+pub fn astar_edge<C: CoordType, D, W, F>(grid: &HexGrid<C, D, W>, start: &C, goal: &C, cost: F) -> Option<Vec<C>>
+        where W: HexGridDataWrapper<C, D>, F: Fn(&D, &D) -> Option<u32> {
+    let goal = &grid.topology().wrap(goal);
+    let heuristic = |c: &C| u32::try_from(grid.distance(c, goal).to_i128()).unwrap_or(u32::MAX);
+    search(grid, start, Some(goal), None, cost, heuristic).path_to(goal)
+}
+
+// Dijkstra
+
+pub fn dijkstra<C: CoordType, D, W, F>(grid: &HexGrid<C, D, W>, start: &C, goal: &C, cost: F) -> Option<Vec<C>>
+        where W: HexGridDataWrapper<C, D>, F: Fn(&D) -> Option<u32> {
+    dijkstra_edge(grid, start, goal, |_, to| cost(to))
+}
+
+pub fn dijkstra_edge<C: CoordType, D, W, F>(grid: &HexGrid<C, D, W>, start: &C, goal: &C, cost: F) -> Option<Vec<C>>
+        where W: HexGridDataWrapper<C, D>, F: Fn(&D, &D) -> Option<u32> {
+    let goal = &grid.topology().wrap(goal);
+    search(grid, start, Some(goal), None, cost, |_| 0).path_to(goal)
+}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{HexGridHashMapWrapper, HexGridRectangleWrapper};
+    use super::super::coords::{CubeCoords, OffsetOddCoords};
+    use super::super::coords::utils::{HexShape, Orientation};
+    use super::super::topology::Topology;
+    use super::super::initializers::{HexagonalCenteredGridIterator, SquareFlatOffsetOddGridIterator};
+
+    type Grid = HexGrid<CubeCoords, u32, HexGridHashMapWrapper<CubeCoords, u32>>;
+
+    // Terrain costs: 0 is a wall, anything else is the cost of entering the tile
+    fn make_grid() -> Grid {
+        let mut grid = Grid::new(HexShape::new(1.0, Orientation::Flat), None);
+        grid.initialize(HexagonalCenteredGridIterator::new(3, Orientation::Flat), &|| 1).unwrap();
+        grid
+    }
+
+    fn terrain(d: &u32) -> Option<u32> {
+        match *d {
+            0 => None,
+            c => Some(c)
+        }
+    }
+
+    fn cube(t: (i32, i32, i32)) -> CubeCoords {
+        CubeCoords::new(t, Orientation::Flat)
+    }
+
+    fn path_cost(grid: &Grid, path: &[CubeCoords]) -> u32 {
+        path[1..].iter().map(|c| *grid.borrow_data_by_key(c.get_key()).unwrap()).sum()
+    }
+
+    #[test]
+    fn test_straight_path() {
+        let grid = make_grid();
+        let (start, goal) = (cube((-3, 3, 0)), cube((3, -3, 0)));
+        let path = astar(&grid, &start, &goal, terrain).unwrap();
+        assert_eq!(path.len(), 7);
+        assert_eq!(path[0], start);
+        assert_eq!(path[6], goal);
+        for pair in path.windows(2) {
+            assert_eq!(pair[0].distance(&pair[1]), 1);
+        }
+        assert_eq!(dijkstra(&grid, &start, &goal, terrain).unwrap().len(), 7);
+        assert_eq!(astar(&grid, &start, &start, terrain), Some(vec![start]));
+    }
+
+    #[test]
+    fn test_path_around_walls() {
+        let mut grid = make_grid();
+        // Wall across the middle of the map with a gap at the top
+        for z in -2..4 {
+            *grid.mut_borrow_data_by_key(cube((0, -z, z)).get_key()).unwrap() = 0;
+        }
+        let (start, goal) = (cube((-2, 2, 0)), cube((2, -2, 0)));
+        let path = astar(&grid, &start, &goal, terrain).unwrap();
+        assert!(path.iter().all(|c| *grid.borrow_data_by_key(c.get_key()).unwrap() != 0));
+        assert!(path.contains(&cube((0, 3, -3))));
+        assert_eq!(path_cost(&grid, &path), path_cost(&grid, &dijkstra(&grid, &start, &goal, terrain).unwrap()));
+
+        // Closing the gap leaves no path
+        *grid.mut_borrow_data_by_key(cube((0, 3, -3)).get_key()).unwrap() = 0;
+        assert_eq!(astar(&grid, &start, &goal, terrain), None);
+        assert_eq!(dijkstra(&grid, &start, &goal, terrain), None);
+    }
+
+    #[test]
+    fn test_path_prefers_cheap_terrain() {
+        let mut grid = make_grid();
+        // Expensive swamp along the direct route
+        for x in -1..2 {
+            *grid.mut_borrow_data_by_key(cube((x, -x, 0)).get_key()).unwrap() = 10;
+        }
+        let (start, goal) = (cube((-2, 2, 0)), cube((2, -2, 0)));
+        let astar_path = astar(&grid, &start, &goal, terrain).unwrap();
+        let dijkstra_path = dijkstra(&grid, &start, &goal, terrain).unwrap();
+        assert_eq!(path_cost(&grid, &astar_path), 5);
+        assert_eq!(path_cost(&grid, &dijkstra_path), 5);
+    }
+
+    #[test]
+    fn test_edge_costs() {
+        let mut grid = make_grid();
+        // Heights, climbing costs 5 extra per level and descending is free
+        grid.mutate_all_tiles(|coord, data| *data = if coord.get().0 == 0 { 2 } else { 1 });
+        let climb = |from: &u32, to: &u32| Some(1 + 5 * to.saturating_sub(*from));
+        let (start, goal) = (cube((-1, 1, 0)), cube((1, -1, 0)));
+        let path = astar_edge(&grid, &start, &goal, climb).unwrap();
+        let cost: u32 = path.windows(2).map(|p| {
+            climb(grid.borrow_data_by_key(p[0].get_key()).unwrap(), grid.borrow_data_by_key(p[1].get_key()).unwrap()).unwrap()
+        }).sum();
+        // Crossing the ridge is unavoidable, so the cheapest path climbs exactly once
+        assert_eq!(cost, 6 + 1);
+        assert_eq!(dijkstra_edge(&grid, &start, &goal, climb).unwrap().len(), path.len());
+    }
+
+    #[test]
+    fn test_cost_overflow() {
+        // Routes costing more than a u32 can hold are skipped rather than overflowing
+        let mut grid = make_grid();
+        grid.mutate_all_data(&|d| *d = u32::MAX / 2 + 1);
+        let (start, goal) = (cube((0, 0, 0)), cube((2, -2, 0)));
+        assert_eq!(dijkstra(&grid, &start, &goal, terrain), None);
+        assert_eq!(astar(&grid, &start, &cube((1, -1, 0)), terrain).map(|p| p.len()), Some(2));
+        assert_eq!(grid.reachable(&start, u32::MAX, terrain).costs.len(), 7);
+
+        // The A* estimate can't overflow either
+        grid.mutate_all_data(&|d| *d = u32::MAX);
+        assert_eq!(astar(&grid, &start, &goal, terrain), None);
+    }
+
+    #[test]
+    fn test_dense_wrapper() {
+        let orient = Orientation::Pointy;
+        let mut grid: HexGrid<OffsetOddCoords, bool, HexGridRectangleWrapper<OffsetOddCoords, bool>> =
+            HexGrid::new(HexShape::new(1.0, orient), Some(&|| HexGridRectangleWrapper::with_size(5, 5, orient)));
+        grid.initialize(SquareFlatOffsetOddGridIterator::new(OffsetOddCoords::new((0, 0), orient), 5, 5), &|| true).unwrap();
+        for y in 0..4 {
+            *grid.mut_borrow_data_by_key(OffsetOddCoords::new((2, y), orient).get_key()).unwrap() = false;
+        }
+        let passable = |d: &bool| if *d { Some(1) } else { None };
+        let (start, goal) = (OffsetOddCoords::new((0, 0), orient), OffsetOddCoords::new((4, 0), orient));
+        let path = astar(&grid, &start, &goal, passable).unwrap();
+        assert!(path.contains(&OffsetOddCoords::new((2, 4), orient)));
+        assert_eq!(path.len(), dijkstra(&grid, &start, &goal, passable).unwrap().len());
+    }
+
+    #[test]
+    fn test_wrapping_map() {
+        let orient = Orientation::Flat;
+        let mut grid: HexGrid<OffsetOddCoords, u32, HexGridHashMapWrapper<OffsetOddCoords, u32>> =
+            HexGrid::new(HexShape::new(1.0, orient), None);
+        grid.initialize(SquareFlatOffsetOddGridIterator::new(OffsetOddCoords::new((0, 0), orient), 10, 3), &|| 1).unwrap();
+        let (start, goal) = (OffsetOddCoords::new((1, 1), orient), OffsetOddCoords::new((8, 1), orient));
+        assert_eq!(astar(&grid, &start, &goal, terrain).unwrap().len(), 8);
+
+        grid.set_topology(Topology::Cylinder { width: 10 });
+        let path = astar(&grid, &start, &goal, terrain).unwrap();
+        assert_eq!(path.len(), 4);
+        // Across the seam between the first and last columns
+        assert!(path.iter().any(|c| c.get().0 == 0));
+        assert!(path.iter().any(|c| c.get().0 == 9));
+        assert_eq!(dijkstra(&grid, &start, &goal, terrain).unwrap().len(), 4);
+
+        // A goal off the edge of the map is the same hex as its copy on the map
+        let off_map = OffsetOddCoords::new((-2, 1), orient);
+        assert_eq!(astar(&grid, &start, &off_map, terrain), Some(path));
+        assert_eq!(dijkstra(&grid, &start, &off_map, terrain).unwrap().last(), Some(&goal));
+    }
+
+    #[test]
+    fn test_missing_tiles() {
+        let grid = make_grid();
+        assert_eq!(astar(&grid, &cube((5, -5, 0)), &cube((0, 0, 0)), terrain), None);
+        assert_eq!(astar(&grid, &cube((0, 0, 0)), &cube((5, -5, 0)), terrain), None);
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::{HexGrid, HexGridDataWrapper};
+use super::tile::HexTileRef;
+use super::coords::{CoordType, CoordKey};
+use super::coords::num::CoordInt;
+
+/*
+    Regions of connected tiles
+
+    Tiles belong to a region when their data satisfies a predicate, and two such tiles are
+    connected when they are neighbours in the grid.
+*/
+
+// Bounding range of a set of hexes, as the smallest and largest value along each cube axis
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CubeBounds<I = i32> {
+    pub min: (I, I, I),
+    pub max: (I, I, I)
+}
+
+impl<I: CoordInt> CubeBounds<I> {
+    pub fn new<C: CoordType<Int = I>>(coord: &C) -> Self {
+        let cube = coord.to_cube();
+        Self { min: cube, max: cube }
+    }
+
+    pub fn extend<C: CoordType<Int = I>>(&mut self, coord: &C) {
+        let (x, y, z) = coord.to_cube();
+        self.min = (self.min.0.min(x), self.min.1.min(y), self.min.2.min(z));
+        self.max = (self.max.0.max(x), self.max.1.max(y), self.max.2.max(z));
+    }
+
+    pub fn contains<C: CoordType<Int = I>>(&self, coord: &C) -> bool {
+        let (x, y, z) = coord.to_cube();
+        self.min.0 <= x && x <= self.max.0 && self.min.1 <= y && y <= self.max.1 && self.min.2 <= z && z <= self.max.2
+    }
+}
+
+// Component labels for every tile satisfying the predicate, components are numbered from 0
+#[derive(Clone, Debug, PartialEq)]
+pub struct Components<I: CoordInt = i32> {
+    pub labels: HashMap<CoordKey<I>, usize>,
+    pub sizes: Vec<usize>,
+    pub bounds: Vec<CubeBounds<I>>
+}
+
+impl<I: CoordInt> Components<I> {
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn component_of<C: CoordType<Int = I>>(&self, coord: &C) -> Option<usize> {
+        self.labels.get(&coord.get_key()).cloned()
+    }
+}
+
+// Breadth first walk over the tiles connected to `seed`, skipping tiles already in `visited`
+fn flood<C: CoordType, D, W, P, V>(grid: &HexGrid<C, D, W>, seed: CoordKey<C::Int>, predicate: &P,
+        visited: &mut HashSet<CoordKey<C::Int>>, mut visit: V)
+        where W: HexGridDataWrapper<C, D>, P: Fn(&D) -> bool, V: FnMut(HexTileRef<C, D>) {
+    match grid.borrow_tile_by_key(seed) {
+        Some(tile) if predicate(tile.borrow_data()) && visited.insert(seed) => (),
+        _ => return
+    }
+    let mut frontier = VecDeque::new();
+    frontier.push_back(seed);
+    while let Some(key) = frontier.pop_front() {
+        let tile = grid.borrow_tile_by_key(key).unwrap();
+        visit(tile);
+        for next in grid.neighbours_of(tile.borrow_coord()) {
+            if predicate(next.borrow_data()) && visited.insert(next.get_key()) {
+                frontier.push_back(next.get_key());
+            }
+        }
+    }
+}
+
+pub fn flood_fill<C: CoordType, D, W, P>(grid: &HexGrid<C, D, W>, seed: &C, predicate: P) -> HashSet<CoordKey<C::Int>>
+        where W: HexGridDataWrapper<C, D>, P: Fn(&D) -> bool {
+    let mut filled = HashSet::new();
+    flood(grid, seed.get_key(), &predicate, &mut filled, |_| ());
+    filled
+}
+
+// Components are numbered in the order the grid iterates its tiles
+pub fn connected_components<C: CoordType, D, W, P>(grid: &HexGrid<C, D, W>, predicate: P) -> Components<C::Int>
+        where W: HexGridDataWrapper<C, D>, P: Fn(&D) -> bool {
+    let mut components = Components { labels: HashMap::new(), sizes: Vec::new(), bounds: Vec::new() };
+    let mut visited = HashSet::new();
+    for tile in grid.iter() {
+        if visited.contains(&tile.get_key()) || !predicate(tile.borrow_data()) {
+            continue
+        }
+        let label = components.sizes.len();
+        let mut size = 0;
+        let mut bounds = CubeBounds::new(tile.borrow_coord());
+        flood(grid, tile.get_key(), &predicate, &mut visited, |t| {
+            components.labels.insert(t.get_key(), label);
+            bounds.extend(t.borrow_coord());
+            size += 1;
+        });
+        components.sizes.push(size);
+        components.bounds.push(bounds);
+    }
+    components
+}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{HexGridHashMapWrapper, HexGridRectangleWrapper};
+    use super::super::coords::{CubeCoords, OffsetOddCoords};
+    use super::super::coords::alg::ring;
+    use super::super::coords::utils::{HexShape, Orientation};
+    use super::super::initializers::{HexagonalCenteredGridIterator, SquareFlatOffsetOddGridIterator};
+
+    type Grid = HexGrid<CubeCoords, bool, HexGridHashMapWrapper<CubeCoords, bool>>;
+
+    // Open ground everywhere, with a wall around the ring at distance 2 from the centre
+    fn make_walled_grid() -> Grid {
+        let mut grid = Grid::new(HexShape::new(1.0, Orientation::Flat), None);
+        grid.initialize(HexagonalCenteredGridIterator::new(4, Orientation::Flat), &|| true).unwrap();
+        for coord in ring(&cube((0, 0, 0)), 2) {
+            *grid.mut_borrow_data_by_key(coord.get_key()).unwrap() = false;
+        }
+        grid
+    }
+
+    fn cube(t: (i32, i32, i32)) -> CubeCoords {
+        CubeCoords::new(t, Orientation::Flat)
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = make_walled_grid();
+        let inside = flood_fill(&grid, &cube((0, 0, 0)), |open| *open);
+        assert_eq!(inside.len(), 7);
+        let outside = flood_fill(&grid, &cube((4, -4, 0)), |open| *open);
+        assert_eq!(outside.len(), 61 - 19);
+        assert!(inside.is_disjoint(&outside));
+
+        // Seeds that don't satisfy the predicate or aren't in the grid fill nothing
+        assert!(flood_fill(&grid, &cube((2, -2, 0)), |open| *open).is_empty());
+        assert!(flood_fill(&grid, &cube((9, -9, 0)), |open| *open).is_empty());
+        assert_eq!(flood_fill(&grid, &cube((2, -2, 0)), |open| !*open).len(), 12);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let grid = make_walled_grid();
+        let components = connected_components(&grid, |open| *open);
+        assert_eq!(components.count(), 2);
+        assert_eq!(components.labels.len(), 61 - 12);
+
+        let inner = components.component_of(&cube((0, 0, 0))).unwrap();
+        let outer = components.component_of(&cube((3, 0, -3))).unwrap();
+        assert_ne!(inner, outer);
+        assert_eq!(components.component_of(&cube((2, 0, -2))), None);
+        assert_eq!(components.sizes[inner], 7);
+        assert_eq!(components.sizes[outer], 42);
+        assert_eq!(components.bounds[inner], CubeBounds { min: (-1, -1, -1), max: (1, 1, 1) });
+        assert_eq!(components.bounds[outer], CubeBounds { min: (-4, -4, -4), max: (4, 4, 4) });
+        assert!(components.bounds[inner].contains(&cube((1, -1, 0))));
+        assert!(!components.bounds[inner].contains(&cube((2, -1, -1))));
+
+        // The walls form a single ring
+        let walls = connected_components(&grid, |open| !*open);
+        assert_eq!(walls.count(), 1);
+        assert_eq!(walls.sizes, vec![12]);
+    }
+
+    #[test]
+    fn test_islands_dense() {
+        let orient = Orientation::Flat;
+        let mut grid: HexGrid<OffsetOddCoords, bool, HexGridRectangleWrapper<OffsetOddCoords, bool>> =
+            HexGrid::new(HexShape::new(1.0, orient), Some(&|| HexGridRectangleWrapper::with_size(6, 4, orient)));
+        grid.initialize(SquareFlatOffsetOddGridIterator::new(OffsetOddCoords::new((0, 0), orient), 6, 4), &|| false).unwrap();
+        // Land in columns 0 and 5, water between them
+        grid.mutate_all_tiles(|c, land| *land = c.get().0 == 0 || c.get().0 == 5);
+        let islands = connected_components(&grid, |land| *land);
+        assert_eq!(islands.count(), 2);
+        assert_eq!(islands.sizes, vec![4, 4]);
+        assert_eq!(islands.component_of(&OffsetOddCoords::new((0, 3), orient)), Some(islands.labels[&OffsetOddCoords::new((0, 0), orient).get_key()]));
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+use std::iter::FromIterator;
+use std::slice;
+use std::vec;
+
+use super::coords::{CoordType, CoordKey};
+use super::coords::alg::sum_tuple_3;
+
+/*
+    Shapes built by combining other shapes
+
+    A shape is a set of hexes that remembers the order they were added in, so any of the
+    initializer iterators (or anything else yielding coordinates) can be collected into one,
+    combined with other shapes, and then passed to `HexGrid::initialize` with `into_iter`.
+*/
+
+#[derive(Clone, Debug)]
+pub struct Shape<C: CoordType> {
+    hexes: Vec<C>,
+    keys: HashSet<CoordKey<C::Int>>
+}
+
+impl<C: CoordType> Shape<C> {
+    pub fn new<I>(hexes: I) -> Self where I: IntoIterator<Item=C> {
+        let mut shape = Self { hexes: Vec::new(), keys: HashSet::new() };
+        shape.extend(hexes);
+        shape
+    }
+
+    // Returns false if the hex was already part of the shape
+    pub fn insert(&mut self, coord: C) -> bool {
+        let added = self.keys.insert(coord.get_key());
+        if added {
+            self.hexes.push(coord);
+        }
+        added
+    }
+
+    pub fn contains(&self, coord: &C) -> bool {
+        self.keys.contains(&coord.get_key())
+    }
+
+    pub fn len(&self) -> usize {
+        self.hexes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hexes.is_empty()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, C> {
+        self.hexes.iter()
+    }
+
+    // Set operations, hexes keep the order they had in `self` with any new hexes after them
+
+    pub fn union(mut self, other: Shape<C>) -> Self {
+        self.extend(other);
+        self
+    }
+
+    pub fn intersect(self, other: &Shape<C>) -> Self {
+        self.mask(|c| other.contains(c))
+    }
+
+    pub fn subtract(self, other: &Shape<C>) -> Self {
+        self.mask(|c| !other.contains(c))
+    }
+
+    // Keep only the hexes for which `f` returns true
+    pub fn mask<F>(self, f: F) -> Self where F: Fn(&C) -> bool {
+        self.hexes.into_iter().filter(|c| f(c)).collect()
+    }
+
+    // Transforms
+
+    // Move the shape so that the hex at the cube origin would end up on `to`
+    pub fn translate(self, to: &C) -> Self {
+        let offset = to.to_cube();
+        self.hexes.into_iter().map(|c| C::from_cube(sum_tuple_3(c.to_cube(), offset), c.orientation())).collect()
+    }
+
+    // Rotate the shape around `center` in steps of 60 degrees, positive steps rotate clockwise
+    pub fn rotate(self, center: &C, steps: i32) -> Self {
+        self.hexes.into_iter().map(|c| c.rotate(center, steps)).collect()
+    }
+}
+
+impl<C: CoordType> FromIterator<C> for Shape<C> {
+    fn from_iter<I: IntoIterator<Item=C>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+impl<C: CoordType> Extend<C> for Shape<C> {
+    fn extend<I: IntoIterator<Item=C>>(&mut self, iter: I) {
+        for coord in iter {
+            self.insert(coord);
+        }
+    }
+}
+
+impl<C: CoordType> IntoIterator for Shape<C> {
+    type Item = C;
+    type IntoIter = vec::IntoIter<C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.hexes.into_iter()
+    }
+}
+
+impl<'a, C: CoordType> IntoIterator for &'a Shape<C> {
+    type Item = &'a C;
+    type IntoIter = slice::Iter<'a, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.hexes.iter()
+    }
+}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{HexGrid, HexGridHashMapWrapper};
+    use super::super::coords::{CubeCoords, OffsetOddCoords};
+    use super::super::coords::alg::ring;
+    use super::super::coords::utils::{HexShape, Orientation};
+    use super::super::initializers::{HexagonalCenteredGridIterator, RectangleGridIterator, TriangleGridIterator, TrianglePoint};
+
+    fn cube(t: (i32, i32, i32)) -> CubeCoords {
+        CubeCoords::new(t, Orientation::Flat)
+    }
+
+    fn hexagon(center: (i32, i32, i32), radius: i32) -> Shape<CubeCoords> {
+        Shape::new(HexagonalCenteredGridIterator::centered_at(&cube(center), radius))
+    }
+
+    #[test]
+    fn test_new_removes_duplicates() {
+        let shape = Shape::new(vec![cube((0, 0, 0)), cube((1, -1, 0)), cube((0, 0, 0))]);
+        assert_eq!(shape.len(), 2);
+        assert_eq!(shape.iter().cloned().collect::<Vec<_>>(), vec![cube((0, 0, 0)), cube((1, -1, 0))]);
+        assert!(Shape::<CubeCoords>::new(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = hexagon((0, 0, 0), 2);
+        let b = hexagon((2, -2, 0), 2);
+        // Two radius 2 hexagons two hexes apart share 9 hexes
+        assert_eq!(a.clone().intersect(&b).len(), 9);
+        assert_eq!(a.clone().union(b.clone()).len(), 19 + 19 - 9);
+        assert_eq!(a.clone().subtract(&b).len(), 19 - 9);
+        assert!(a.clone().subtract(&b).iter().all(|c| a.contains(c) && !b.contains(c)));
+
+        // A hexagon with a ring cut out of it leaves an island in the middle
+        let cut = hexagon((0, 0, 0), 3).subtract(&Shape::new(ring(&cube((0, 0, 0)), 2)));
+        assert_eq!(cut.len(), 37 - 12);
+        assert!(cut.contains(&cube((1, -1, 0))));
+        assert!(!cut.contains(&cube((2, -2, 0))));
+        assert!(cut.contains(&cube((3, -3, 0))));
+    }
+
+    #[test]
+    fn test_mask() {
+        // A rectangle with its corners cut off
+        let rect: Shape<OffsetOddCoords> = RectangleGridIterator::new(OffsetOddCoords::new((0, 0), Orientation::Pointy), 6, 4).collect();
+        let corners = [(0, 0), (5, 0), (0, 3), (5, 3)];
+        let cut = rect.clone().mask(|c| !corners.iter().any(|corner| *corner == c.get()));
+        assert_eq!(cut.len(), rect.len() - 4);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let triangle: Shape<CubeCoords> = TriangleGridIterator::new(cube((0, 0, 0)), TrianglePoint::Down, 2).collect();
+        let moved = triangle.clone().translate(&cube((3, -1, -2)));
+        assert_eq!(moved.len(), triangle.len());
+        assert!(moved.contains(&cube((3, -1, -2))));
+        assert!(triangle.iter().all(|c| moved.contains(&cube((c.get().0 + 3, c.get().1 - 1, c.get().2 - 2)))));
+
+        let center = cube((0, 0, 0));
+        let turned = triangle.clone().rotate(&center, 2);
+        assert_eq!(turned.len(), triangle.len());
+        assert!(!turned.clone().subtract(&triangle).is_empty());
+        let full_turn = triangle.clone().rotate(&center, 6);
+        assert_eq!(full_turn.iter().cloned().collect::<Vec<_>>(), triangle.iter().cloned().collect::<Vec<_>>());
+        assert!(turned.rotate(&center, -2).subtract(&triangle).is_empty());
+    }
+
+    #[test]
+    fn test_initialize_grid() {
+        let shape = hexagon((0, 0, 0), 4).subtract(&hexagon((0, 0, 0), 1));
+        let mut grid: HexGrid<CubeCoords, u8, HexGridHashMapWrapper<CubeCoords, u8>> =
+            HexGrid::new(HexShape::new(1.0, Orientation::Flat), None);
+        grid.initialize(shape.clone().into_iter(), &|| 0).unwrap();
+        assert_eq!(grid.iter().count(), 61 - 7);
+        assert!(grid.borrow_tile_by_key(cube((0, 0, 0)).get_key()).is_none());
+        assert!(shape.iter().all(|c| grid.borrow_tile_by_key(c.get_key()).is_some()));
+    }
+}
@@ -1,46 +1,163 @@
-use super::coords::{self, CoordType, CoordKey};
-
-pub struct HexTile<C: CoordType, D>
-{
-    coord: C,
-    data: D
-}
-
-impl<C: CoordType, D> HexTile<C, D> 
-{
-    pub fn new(coord: C, data: D) -> Self {
-        Self { coord, data }
-    }
-
-    pub fn get_key(&self) -> CoordKey {
-        self.coord.get_key()
-    }
-
-    pub fn borrow_data(&self) -> &D {
-        &self.data
-    }
-
-    pub fn mut_borrow_data(&mut self) -> &mut D {
-        &mut self.data
-    }
-
-    pub fn mutate_data(&mut self, f: &Fn(&mut D)) {
-        f(&mut self.data)
-    }
-
-    pub fn set_data(&mut self, new: D) {
-        self.data = new;
-    }
-
-    pub fn borrow_coord(&self) -> &C {
-        &self.coord
-    }
-
-    pub fn to_pixel(&self, radius: f32) -> coords::PixelCoord {
-        self.coord.to_pixel(radius)
-    }
-
-    pub fn to_pixels(&self, radius: f32) -> [(f32, f32); 6] {
-        self.coord.to_pixel(radius).corners()
-    }
-}
\ No newline at end of file
+use super::coords::{self, CoordType, CoordKey};
+use super::coords::num::CoordFloat;
+use super::coords::utils::Layout;
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HexTile<C: CoordType, D>
+{
+    coord: C,
+    data: D
+}
+
+impl<C: CoordType, D> HexTile<C, D>
+{
+    pub fn new(coord: C, data: D) -> Self {
+        Self { coord, data }
+    }
+
+    pub fn get_key(&self) -> CoordKey<C::Int> {
+        self.coord.get_key()
+    }
+
+    pub fn borrow_data(&self) -> &D {
+        &self.data
+    }
+
+    pub fn mut_borrow_data(&mut self) -> &mut D {
+        &mut self.data
+    }
+
+    pub fn mutate_data(&mut self, f: &Fn(&mut D)) {
+        f(&mut self.data)
+    }
+
+    pub fn set_data(&mut self, new: D) {
+        self.data = new;
+    }
+
+    pub fn into_data(self) -> D {
+        self.data
+    }
+
+    pub fn borrow_coord(&self) -> &C {
+        &self.coord
+    }
+
+    pub fn mut_borrow_coord_and_data(&mut self) -> (&C, &mut D) {
+        (&self.coord, &mut self.data)
+    }
+}
+
+/*
+    Tiles borrowed from a grid
+
+    Grid wrappers hand these out rather than references to a `HexTile`, so that wrappers which
+    only store the data (like the dense wrappers) can rebuild the coordinate of each tile.
+*/
+
+pub struct HexTileRef<'a, C: CoordType, D: 'a> {
+    coord: C,
+    data: &'a D
+}
+
+impl<'a, C: CoordType, D> HexTileRef<'a, C, D> {
+    pub fn new(coord: C, data: &'a D) -> Self {
+        Self { coord, data }
+    }
+
+    pub fn get_key(&self) -> CoordKey<C::Int> {
+        self.coord.get_key()
+    }
+
+    pub fn borrow_data(&self) -> &'a D {
+        self.data
+    }
+
+    pub fn borrow_coord(&self) -> &C {
+        &self.coord
+    }
+}
+
+impl<'a, C: CoordType, D> Clone for HexTileRef<'a, C, D> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, C: CoordType, D> Copy for HexTileRef<'a, C, D> {}
+
+impl<'a, C: CoordType, D> From<&'a HexTile<C, D>> for HexTileRef<'a, C, D> {
+    fn from(tile: &'a HexTile<C, D>) -> Self {
+        Self::new(tile.coord, &tile.data)
+    }
+}
+
+pub struct HexTileMut<'a, C: CoordType, D: 'a> {
+    coord: C,
+    data: &'a mut D
+}
+
+impl<'a, C: CoordType, D> HexTileMut<'a, C, D> {
+    pub fn new(coord: C, data: &'a mut D) -> Self {
+        Self { coord, data }
+    }
+
+    pub fn get_key(&self) -> CoordKey<C::Int> {
+        self.coord.get_key()
+    }
+
+    pub fn borrow_data(&self) -> &D {
+        self.data
+    }
+
+    pub fn mut_borrow_data(&mut self) -> &mut D {
+        self.data
+    }
+
+    pub fn mutate_data(&mut self, f: &Fn(&mut D)) {
+        f(self.data)
+    }
+
+    pub fn set_data(&mut self, new: D) {
+        *self.data = new;
+    }
+
+    // Keep the borrow of the data for as long as the grid is borrowed
+    pub fn into_data(self) -> &'a mut D {
+        self.data
+    }
+
+    pub fn borrow_coord(&self) -> &C {
+        &self.coord
+    }
+
+    pub fn mut_borrow_coord_and_data(&mut self) -> (&C, &mut D) {
+        (&self.coord, self.data)
+    }
+}
+
+impl<'a, C: CoordType, D> From<&'a mut HexTile<C, D>> for HexTileMut<'a, C, D> {
+    fn from(tile: &'a mut HexTile<C, D>) -> Self {
+        Self::new(tile.coord, &mut tile.data)
+    }
+}
+
+// Pixel positions are the same for owned and borrowed tiles
+
+macro_rules! impl_tile_pixels {
+    ($([$($lifetime:lifetime)*] $type:ty),*) => {
+        $(
+            impl<$($lifetime,)* C: CoordType, D> $type {
+                pub fn to_pixel<F: CoordFloat, L: Into<Layout<F>>>(&self, layout: L) -> coords::PixelCoord<F> {
+                    self.coord.to_pixel(layout)
+                }
+
+                pub fn to_pixels<F: CoordFloat, L: Into<Layout<F>>>(&self, layout: L) -> [(F, F); 6] {
+                    self.coord.to_pixel(layout).corners()
+                }
+            }
+        )*
+    }
+}
+
+impl_tile_pixels!([] HexTile<C, D>, ['a] HexTileRef<'a, C, D>, ['a] HexTileMut<'a, C, D>);
@@ -0,0 +1,281 @@
+use super::coords::{CoordType, OffsetOddCoords};
+use super::coords::num::{self, CoordInt};
+use super::coords::alg::{distance_cube, line_cube, rotate_cube, sum_tuple_3};
+use super::coords::utils::Orientation;
+
+/*
+    How the edges of a map connect to each other
+
+    Wrapping maps store every tile at a single canonical coordinate inside the map, and any
+    coordinate outside it is wrapped back onto that canonical coordinate.
+
+    Rectangular wrapping maps cover offset odd coordinates from (0, 0) to (width - 1, height - 1),
+    which is the shape built by `SquareFlatOffsetOddGridIterator` (and stored by
+    `HexGridRectangleWrapper`). Wrapping has to keep the shifted rows or columns of the offset
+    layout lined up, so flat-top maps need an even width, and pointy-top tori an even height.
+
+    Hexagonal wrapping maps cover every hex within `radius` of the cube origin, and hexes past
+    one edge wrap around onto the opposite edge (translating by one of six mirror centers).
+
+    Sizes are in the integer type of the coordinates being wrapped.
+*/
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Topology<I = i32> {
+    #[default]
+    Plane,
+    Cylinder { width: I },
+    Torus { width: I, height: I },
+    Hexagon { radius: I }
+}
+
+// The remainder is always smaller than `modulus`, so it fits the integer type
+fn rem_euclid<I: CoordInt>(value: I, modulus: I) -> I {
+    I::from_i128(value.to_i128().rem_euclid(modulus.to_i128())).unwrap_or_else(|| num::overflow())
+}
+
+// The centers of the six copies of a hexagonal map around it, each one step of rotation from the last
+fn hexagon_mirrors<I: CoordInt>(radius: I) -> [(I, I, I); 6] {
+    let center = (radius.plus(radius).plus(I::ONE), radius.negate().minus(I::ONE), radius.negate());
+    [0, 1, 2, 3, 4, 5].map(|steps| rotate_cube(center, (I::ZERO, I::ZERO, I::ZERO), steps))
+}
+
+impl<I: CoordInt> Topology<I> {
+    pub fn is_valid_for(&self, orientation: Orientation) -> bool {
+        match (*self, orientation) {
+            (Topology::Plane, _) => true,
+            (Topology::Cylinder { width }, Orientation::Flat) => width > I::ZERO && !width.is_odd(),
+            (Topology::Cylinder { width }, Orientation::Pointy) => width > I::ZERO,
+            (Topology::Torus { width, height }, Orientation::Flat) => width > I::ZERO && !width.is_odd() && height > I::ZERO,
+            (Topology::Torus { width, height }, Orientation::Pointy) => width > I::ZERO && height > I::ZERO && !height.is_odd(),
+            (Topology::Hexagon { radius }, _) => radius >= I::ZERO
+        }
+    }
+
+    // Cube offsets that map the canonical area onto each of its copies, besides (0, 0, 0)
+    fn translations(&self, orientation: Orientation) -> Vec<(I, I, I)> {
+        let offset = |col, row| OffsetOddCoords::new((col, row), orientation).to_cube();
+        match *self {
+            Topology::Plane => vec![],
+            Topology::Cylinder { width } => vec![offset(width, I::ZERO), offset(width.negate(), I::ZERO)],
+            Topology::Torus { width, height } => {
+                let mut result = Vec::new();
+                for col in [width.negate(), I::ZERO, width].iter() {
+                    for row in [height.negate(), I::ZERO, height].iter() {
+                        if (*col, *row) != (I::ZERO, I::ZERO) {
+                            result.push(sum_tuple_3(offset(*col, I::ZERO), offset(I::ZERO, *row)));
+                        }
+                    }
+                }
+                result
+            },
+            Topology::Hexagon { radius } => hexagon_mirrors(radius).to_vec()
+        }
+    }
+
+    pub fn wrap_cube(&self, cube: (I, I, I), orientation: Orientation) -> (I, I, I) {
+        match *self {
+            Topology::Plane => cube,
+            Topology::Cylinder { width } => {
+                let (col, row) = OffsetOddCoords::from_cube(cube, orientation).get();
+                OffsetOddCoords::new((rem_euclid(col, width), row), orientation).to_cube()
+            },
+            Topology::Torus { width, height } => {
+                let (col, row) = OffsetOddCoords::from_cube(cube, orientation).get();
+                OffsetOddCoords::new((rem_euclid(col, width), rem_euclid(row, height)), orientation).to_cube()
+            },
+            Topology::Hexagon { radius } => {
+                // The copies of the map are centered on a lattice spanned by two neighbouring mirror
+                // centers. Rounding the position of the hex in that lattice lands on the center of
+                // its copy, or of a copy next to it. Mirror centers fit the integer type, so none of
+                // the products below come near the range of `i128`.
+                let wide = |(x, y, z): (I, I, I)| (x.to_i128(), y.to_i128(), z.to_i128());
+                let mirrors = hexagon_mirrors(radius).map(wide);
+                let ((ax, ay, az), (bx, by, bz), (px, py, pz)) = (mirrors[0], mirrors[1], wide(cube));
+                // The number of hexes on the map, which is odd
+                let det = ax * bz - az * bx;
+                let round = |n: i128| (n + det / 2).div_euclid(det);
+                let (u, v) = (round(px * bz - pz * bx), round(ax * pz - az * px));
+                let home = (u * ax + v * bx, u * ay + v * by, u * az + v * bz);
+
+                let offset = |(mx, my, mz): (i128, i128, i128)| (px - home.0 - mx, py - home.1 - my, pz - home.2 - mz);
+                let size = |(x, y, z): (i128, i128, i128)| x.abs().max(y.abs()).max(z.abs());
+                let (x, y, z) = mirrors.iter().map(|m| offset(*m)).fold(offset((0, 0, 0)), |best, o| {
+                    if size(o) < size(best) { o } else { best }
+                });
+                let int = |value: i128| I::from_i128(value).unwrap_or_else(|| num::overflow());
+                (int(x), int(y), int(z))
+            }
+        }
+    }
+
+    // The copy of `to` closest to `from`, both given in canonical form
+    fn nearest_image(&self, from: (I, I, I), to: (I, I, I), orientation: Orientation) -> (I, I, I) {
+        self.translations(orientation).into_iter()
+            .map(|t| sum_tuple_3(to, t))
+            .fold(to, |best, image| {
+                if distance_cube(from, image) < distance_cube(from, best) { image } else { best }
+            })
+    }
+
+    pub fn wrap<C: CoordType<Int = I>>(&self, coord: &C) -> C {
+        match *self {
+            Topology::Plane => *coord,
+            _ => C::from_cube(self.wrap_cube(coord.to_cube(), coord.orientation()), coord.orientation())
+        }
+    }
+
+    // Neighbours, wrapped onto the map. On very small maps the same hex can appear more than once
+    pub fn neighbours<C: CoordType<Int = I>>(&self, coord: &C) -> [C; 6] {
+        coord.neighbours().map(|n| self.wrap(&n))
+    }
+
+    pub fn distance<C: CoordType<Int = I>>(&self, a: &C, b: &C) -> I {
+        let orientation = a.orientation();
+        let (a, b) = (self.wrap_cube(a.to_cube(), orientation), self.wrap_cube(b.to_cube(), orientation));
+        distance_cube(a, self.nearest_image(a, b, orientation))
+    }
+
+    // The line takes the shortest way round the map, every hex on it is wrapped onto the map
+    pub fn line<C: CoordType<Int = I>>(&self, a: &C, b: &C) -> Vec<C> {
+        let orientation = a.orientation();
+        let (a, b) = (self.wrap_cube(a.to_cube(), orientation), self.wrap_cube(b.to_cube(), orientation));
+        line_cube(a, self.nearest_image(a, b, orientation)).into_iter()
+            .map(|cube| C::from_cube(self.wrap_cube(cube, orientation), orientation))
+            .collect()
+    }
+}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use super::super::coords::{CubeCoords, AxialCoords};
+    use super::super::coords::alg::{spiral, sub_tuple_3};
+    use super::super::initializers::SquareFlatOffsetOddGridIterator;
+
+    fn offset(col: i32, row: i32, orientation: Orientation) -> OffsetOddCoords {
+        OffsetOddCoords::new((col, row), orientation)
+    }
+
+    #[test]
+    fn test_validity() {
+        assert!(Topology::Cylinder { width: 6 }.is_valid_for(Orientation::Flat));
+        assert!(!Topology::Cylinder { width: 5 }.is_valid_for(Orientation::Flat));
+        assert!(Topology::Cylinder { width: 5 }.is_valid_for(Orientation::Pointy));
+        assert!(!Topology::Torus { width: 5, height: 5 }.is_valid_for(Orientation::Pointy));
+        assert!(Topology::Torus { width: 5, height: 4 }.is_valid_for(Orientation::Pointy));
+        assert!(!Topology::Hexagon { radius: -1 }.is_valid_for(Orientation::Flat));
+        assert_eq!(Topology::<i32>::default(), Topology::Plane);
+    }
+
+    #[test]
+    fn test_cylinder() {
+        for orient in [Orientation::Flat, Orientation::Pointy].iter() {
+            let topology = Topology::Cylinder { width: 8 };
+            assert_eq!(topology.wrap(&offset(-1, 2, *orient)), offset(7, 2, *orient));
+            assert_eq!(topology.wrap(&offset(8, 2, *orient)), offset(0, 2, *orient));
+            assert_eq!(topology.wrap(&offset(3, 9, *orient)), offset(3, 9, *orient));
+
+            // Every hex on the map keeps six distinct neighbours on the map, which neighbour it back
+            let map: HashSet<OffsetOddCoords> = SquareFlatOffsetOddGridIterator::new(offset(0, 0, *orient), 8, 6).collect();
+            for coord in map.iter() {
+                let neighbours = topology.neighbours(coord);
+                assert_eq!(neighbours.iter().collect::<HashSet<_>>().len(), 6);
+                for n in neighbours.iter() {
+                    assert_eq!(topology.distance(coord, n), 1);
+                    if n.get().1 >= 0 && n.get().1 < 6 {
+                        assert!(map.contains(n));
+                        assert!(topology.neighbours(n).contains(coord));
+                    }
+                }
+            }
+        }
+        let topology = Topology::Cylinder { width: 8 };
+        assert_eq!(topology.distance(&offset(0, 0, Orientation::Pointy), &offset(7, 0, Orientation::Pointy)), 1);
+        assert_eq!(topology.distance(&offset(1, 0, Orientation::Pointy), &offset(5, 0, Orientation::Pointy)), 4);
+        assert_eq!(Topology::Plane.distance(&offset(0, 0, Orientation::Pointy), &offset(7, 0, Orientation::Pointy)), 7);
+    }
+
+    #[test]
+    fn test_torus() {
+        let orient = Orientation::Pointy;
+        let topology = Topology::Torus { width: 6, height: 4 };
+        assert_eq!(topology.wrap(&offset(-1, -1, orient)), offset(5, 3, orient));
+        assert_eq!(topology.distance(&offset(0, 0, orient), &offset(0, 3, orient)), 1);
+        assert_eq!(topology.distance(&offset(0, 0, orient), &offset(5, 3, orient)), 1);
+        assert_eq!(topology.distance(&offset(0, 0, orient), &offset(3, 2, orient)), 4);
+
+        let map: HashSet<OffsetOddCoords> = SquareFlatOffsetOddGridIterator::new(offset(0, 0, orient), 6, 4).collect();
+        for coord in map.iter() {
+            for n in topology.neighbours(coord).iter() {
+                assert!(map.contains(n));
+                assert!(topology.neighbours(n).contains(coord));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hexagon() {
+        let orient = Orientation::Flat;
+        let topology = Topology::Hexagon { radius: 2 };
+        let map: HashSet<CubeCoords> = spiral(&CubeCoords::new((0, 0, 0), orient), 2).collect();
+        // Every hex off the edge of the map lands on a distinct hex on the map
+        for coord in map.iter() {
+            let neighbours = topology.neighbours(coord);
+            assert_eq!(neighbours.iter().collect::<HashSet<_>>().len(), 6);
+            for n in neighbours.iter() {
+                assert!(map.contains(n));
+                assert!(topology.neighbours(n).contains(coord));
+            }
+        }
+        // No two hexes on the map are further apart than the radius
+        for a in map.iter() {
+            for b in map.iter() {
+                assert!(topology.distance(a, b) <= 2);
+            }
+        }
+        let far = CubeCoords::new((40, -17, -23), orient);
+        assert!(map.contains(&topology.wrap(&far)));
+    }
+
+    #[test]
+    fn test_hexagon_wrap_matches_stepping() {
+        // Moving a hex one mirror at a time towards the map always ends on the same hex
+        for radius in 0..6 {
+            let topology = Topology::Hexagon { radius };
+            let mirrors = hexagon_mirrors(radius);
+            for cube in spiral(&CubeCoords::new((3, -1, -2), Orientation::Flat), 25) {
+                let mut stepped = cube.get();
+                while distance_cube(stepped, (0, 0, 0)) > radius {
+                    let nearest = mirrors.iter().min_by_key(|m| distance_cube(stepped, **m)).unwrap();
+                    stepped = sub_tuple_3(stepped, *nearest);
+                }
+                assert_eq!(topology.wrap_cube(cube.get(), Orientation::Flat), stepped);
+            }
+        }
+
+        // Far from the map, in a wider integer type
+        let topology = Topology::Hexagon { radius: 1000i64 };
+        let far = (3_000_000_000_000_123i64, -1_000_000_000_000_000, -2_000_000_000_000_123);
+        let wrapped = topology.wrap_cube(far, Orientation::Pointy);
+        assert!(distance_cube(wrapped, (0, 0, 0)) <= 1000);
+        let [a, ..] = hexagon_mirrors(1000i64);
+        assert_eq!(topology.wrap_cube(sum_tuple_3(wrapped, a), Orientation::Pointy), wrapped);
+    }
+
+    #[test]
+    fn test_line_crosses_seam() {
+        let orient = Orientation::Pointy;
+        let topology = Topology::Cylinder { width: 10 };
+        let line = topology.line(&AxialCoords::from(&offset(1, 0, orient)), &AxialCoords::from(&offset(8, 0, orient)));
+        let cols: Vec<i32> = line.iter().map(|c| OffsetOddCoords::from(c).get().0).collect();
+        assert_eq!(cols, vec![1, 0, 9, 8]);
+        assert_eq!(Topology::Plane.line(&offset(1, 0, orient), &offset(8, 0, orient)).len(), 8);
+    }
+}
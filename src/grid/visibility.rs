@@ -0,0 +1,258 @@
+use std::collections::HashSet;
+use std::f64::consts::PI;
+
+use super::{HexGrid, HexGridDataWrapper};
+use super::coords::{CoordType, CoordKey};
+use super::coords::alg::{ring, sub_tuple_3};
+use super::coords::num::CoordInt;
+use super::coords::utils::HexShape;
+
+/*
+    Field of view from a tile, out to a radius
+
+    Tiles that block sight are themselves visible, but hide whatever is behind them. Coordinates
+    with no tile in the grid are never visible and don't block sight. On wrapping maps sight
+    carries on round the map, so large radii can see the same tile from more than one side.
+*/
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum FovMode {
+    // Draw a line to every hex on the edge of the radius, stopping at the first blocking tile
+    RayCast,
+    // Walk outwards ring by ring, keeping track of the arcs hidden by blocking tiles
+    Shadowcast
+}
+
+pub fn visible_from<C: CoordType, D, W, F>(grid: &HexGrid<C, D, W>, origin: &C, radius: C::Int, mode: FovMode, blocks: F)
+        -> HashSet<CoordKey<C::Int>> where W: HexGridDataWrapper<C, D>, F: Fn(&D) -> bool {
+    match mode {
+        FovMode::RayCast => ray_cast(grid, origin, radius.magnitude(), blocks),
+        FovMode::Shadowcast => shadowcast(grid, origin, radius.magnitude(), blocks)
+    }
+}
+
+// Ray casting
+
+fn ray_cast<C: CoordType, D, W, F>(grid: &HexGrid<C, D, W>, origin: &C, radius: C::Int, blocks: F)
+        -> HashSet<CoordKey<C::Int>> where W: HexGridDataWrapper<C, D>, F: Fn(&D) -> bool {
+    let mut visible = HashSet::new();
+    if grid.borrow_tile_by_key(origin.get_key()).is_some() {
+        visible.insert(origin.get_key());
+    }
+    for target in ring(origin, radius) {
+        for coord in grid.line(origin, &target).iter().skip(1) {
+            if let Some(data) = grid.borrow_data_by_key(coord.get_key()) {
+                visible.insert(coord.get_key());
+                if blocks(data) {
+                    break
+                }
+            }
+        }
+    }
+    visible
+}
+
+// Shadowcasting
+
+// Angles are measured in turns around the centre of the origin. Each hex spans the angles between
+// its outermost corners, and is visible unless the whole of that span is in shadow.
+const ARC_EPSILON: f64 = 1e-9;
+
+fn hex_arc<C: CoordType>(origin: &C, coord: &C) -> (f64, f64) {
+    // Measured relative to the origin to keep the pixel positions small
+    let relative = sub_tuple_3(coord.to_cube(), origin.to_cube());
+    let pixel = C::from_cube(relative, origin.orientation()).to_pixel(HexShape::new(1.0f64, origin.orientation()));
+    let turns = |(px, py): (f64, f64)| py.atan2(px) / (2.0 * PI);
+
+    // Corners are taken relative to the centre so that hexes lying across the start of the turn
+    // don't wrap round, no hex other than the origin spans as much as half a turn
+    let center = turns(pixel.get());
+    let offsets = pixel.corners().map(|corner| {
+        let offset = turns(corner) - center;
+        offset - offset.round()
+    });
+    let start = offsets.iter().cloned().fold(0.0, f64::min);
+    let end = offsets.iter().cloned().fold(0.0, f64::max);
+    (center + start, center + end)
+}
+
+fn add_arc(arcs: &mut Vec<(f64, f64)>, (start, end): (f64, f64)) {
+    // Copies a turn either side make arcs crossing the start of the turn easy to merge and test
+    for offset in &[-1.0, 0.0, 1.0] {
+        arcs.push((start + offset, end + offset));
+    }
+}
+
+fn merge_arcs(arcs: &mut Vec<(f64, f64)>) {
+    arcs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let mut merged: Vec<(f64, f64)> = Vec::with_capacity(arcs.len());
+    for &(start, end) in arcs.iter() {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + ARC_EPSILON => last.1 = last.1.max(end),
+            _ => merged.push((start, end))
+        }
+    }
+    *arcs = merged;
+}
+
+// Shadows are merged, so a hex is hidden only when a single shadow covers it
+fn is_shadowed(arcs: &[(f64, f64)], (start, end): (f64, f64)) -> bool {
+    arcs.iter().any(|&(shadow_start, shadow_end)| shadow_start <= start + ARC_EPSILON && end - ARC_EPSILON <= shadow_end)
+}
+
+fn shadowcast<C: CoordType, D, W, F>(grid: &HexGrid<C, D, W>, origin: &C, radius: C::Int, blocks: F)
+        -> HashSet<CoordKey<C::Int>> where W: HexGridDataWrapper<C, D>, F: Fn(&D) -> bool {
+    let mut visible = HashSet::new();
+    if grid.borrow_tile_by_key(origin.get_key()).is_some() {
+        visible.insert(origin.get_key());
+    }
+    let mut shadows = Vec::new();
+    let mut r = C::Int::ZERO;
+    while r < radius {
+        r = r.plus(C::Int::ONE);
+        let mut new_shadows = Vec::new();
+        for coord in ring(origin, r) {
+            let arc = hex_arc(origin, &coord);
+            let coord = grid.topology().wrap(&coord);
+            let data = match grid.borrow_data_by_key(coord.get_key()) {
+                Some(data) => data,
+                None => continue
+            };
+            if !is_shadowed(&shadows, arc) {
+                visible.insert(coord.get_key());
+            }
+            // Hidden walls still cast shadows past the edges of the shadow hiding them
+            if blocks(data) {
+                add_arc(&mut new_shadows, arc);
+            }
+        }
+        if !new_shadows.is_empty() {
+            shadows.append(&mut new_shadows);
+            merge_arcs(&mut shadows);
+            // Nothing further out can be seen once a single shadow covers the whole turn
+            if shadows.iter().any(|&(start, end)| start < 0.0 && end > 1.0) {
+                break
+            }
+        }
+    }
+    visible
+}
+
+/*
+    TESTING!!!!
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::HexGridHashMapWrapper;
+    use super::super::coords::{CubeCoords, AxialCoords};
+    use super::super::coords::alg::spiral;
+    use super::super::coords::utils::{HexShape, Orientation};
+    use super::super::initializers::HexagonalCenteredGridIterator;
+
+    type Grid = HexGrid<CubeCoords, bool, HexGridHashMapWrapper<CubeCoords, bool>>;
+
+    const MODES: [FovMode; 2] = [FovMode::RayCast, FovMode::Shadowcast];
+
+    fn make_grid(range: i32) -> Grid {
+        let mut grid = Grid::new(HexShape::new(1.0, Orientation::Flat), None);
+        grid.initialize(HexagonalCenteredGridIterator::new(range, Orientation::Flat), &|| false).unwrap();
+        grid
+    }
+
+    fn cube(t: (i32, i32, i32)) -> CubeCoords {
+        CubeCoords::new(t, Orientation::Flat)
+    }
+
+    fn set_wall(grid: &mut Grid, t: (i32, i32, i32)) {
+        *grid.mut_borrow_data_by_key(cube(t).get_key()).unwrap() = true;
+    }
+
+    #[test]
+    fn test_open_field() {
+        let grid = make_grid(6);
+        for mode in MODES.iter() {
+            let origin = cube((0, 0, 0));
+            let visible = visible_from(&grid, &origin, 4, *mode, |wall| *wall);
+            assert_eq!(visible.len(), 61);
+            assert!(visible.iter().all(|key| CubeCoords::from_key(*key, Orientation::Flat).distance(&origin) <= 4));
+
+            // Off-centre origins are clipped by the edge of the map
+            let corner = visible_from(&grid, &cube((6, -6, 0)), 1, *mode, |wall| *wall);
+            assert_eq!(corner.len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_single_wall() {
+        let mut grid = make_grid(5);
+        set_wall(&mut grid, (1, -1, 0));
+        for mode in MODES.iter() {
+            let visible = visible_from(&grid, &cube((0, 0, 0)), 5, *mode, |wall| *wall);
+            assert!(visible.contains(&cube((1, -1, 0)).get_key()));
+            for x in 2..6 {
+                assert!(!visible.contains(&cube((x, -x, 0)).get_key()), "{:?} {}", mode, x);
+            }
+            // Hexes beside the shadow are still visible
+            assert!(visible.contains(&cube((2, -1, -1)).get_key()));
+            assert!(visible.contains(&cube((1, -2, 1)).get_key()));
+        }
+    }
+
+    #[test]
+    fn test_enclosed() {
+        let mut grid = make_grid(4);
+        for coord in ring(&cube((0, 0, 0)), 2) {
+            set_wall(&mut grid, coord.get());
+        }
+        for mode in MODES.iter() {
+            let visible = visible_from(&grid, &cube((0, 0, 0)), 4, *mode, |wall| *wall);
+            assert_eq!(visible.len(), 19, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_shadowcast_gap() {
+        let mut grid = make_grid(5);
+        // Two adjacent walls leave no gap between them for sight to pass through
+        set_wall(&mut grid, (1, -1, 0));
+        set_wall(&mut grid, (1, 0, -1));
+        let visible = visible_from(&grid, &cube((0, 0, 0)), 5, FovMode::Shadowcast, |wall| *wall);
+        assert!(!visible.contains(&cube((2, -1, -1)).get_key()));
+        assert!(!visible.contains(&cube((4, -2, -2)).get_key()));
+        assert!(visible.contains(&cube((0, -1, 1)).get_key()));
+    }
+
+    #[test]
+    fn test_partly_hidden() {
+        // Part of (3, -2, -1) sticks out past the edge of the wall's shadow, which shadowcasting
+        // sees but none of the rays reach
+        let mut grid = make_grid(5);
+        set_wall(&mut grid, (2, -1, -1));
+        let (origin, partly) = (cube((0, 0, 0)), cube((3, -2, -1)));
+        let (wall, behind) = (hex_arc(&origin, &cube((2, -1, -1))), hex_arc(&origin, &partly));
+        assert!(behind.0 < wall.1 && wall.0 < behind.1);
+        assert!(behind.0 < wall.0 || wall.1 < behind.1);
+
+        let ray_cast = visible_from(&grid, &origin, 4, FovMode::RayCast, |wall| *wall);
+        let shadowcast = visible_from(&grid, &origin, 4, FovMode::Shadowcast, |wall| *wall);
+        assert!(!ray_cast.contains(&partly.get_key()));
+        assert!(shadowcast.contains(&partly.get_key()));
+        assert!(!shadowcast.contains(&cube((4, -2, -2)).get_key()));
+    }
+
+    #[test]
+    fn test_other_coord_types() {
+        let mut grid: HexGrid<AxialCoords, bool, HexGridHashMapWrapper<AxialCoords, bool>> =
+            HexGrid::new(HexShape::new(1.0, Orientation::Pointy), None);
+        let origin = AxialCoords::new((0, 0), Orientation::Pointy);
+        grid.initialize(spiral(&origin, 3), &|| false).unwrap();
+        *grid.mut_borrow_data_by_key(AxialCoords::new((1, 0), Orientation::Pointy).get_key()).unwrap() = true;
+        for mode in MODES.iter() {
+            let visible = visible_from(&grid, &origin, 3, *mode, |wall| *wall);
+            assert!(!visible.contains(&AxialCoords::new((3, 0), Orientation::Pointy).get_key()));
+            assert!(visible.contains(&AxialCoords::new((-3, 0), Orientation::Pointy).get_key()));
+        }
+    }
+}
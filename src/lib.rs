@@ -1,4 +1,9 @@
-
-pub mod grid;
-
-pub use grid::coords::utils::{HexShape, Orientation};
\ No newline at end of file
+
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+pub mod grid;
+
+pub use grid::coords::utils::{HexShape, Orientation, Layout};
+pub use grid::coords::direction::{Direction, Diagonal};
\ No newline at end of file